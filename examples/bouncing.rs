@@ -0,0 +1,43 @@
+use slowpoke::*;
+
+// Double-buffered animation: with tracing off, each frame is cleared and drawn
+// out of sight, then shown all at once by `update()`
+fn main() {
+    TurtleArgs::default()
+        .with_size(400, 400)
+        .with_title("bouncing")
+        .run(|turtle| {
+            turtle.hideturtle();
+            turtle.penup();
+            turtle.tracer(0, None);
+
+            let colors = ["red", "green", "blue", "orange"];
+            let mut balls: Vec<([f32; 2], [f32; 2])> = (0..colors.len())
+                .map(|idx| {
+                    let idx = idx as f32;
+                    ([idx * 40. - 60., idx * 30.], [3. + idx, 5. - idx * 2.])
+                })
+                .collect();
+
+            loop {
+                turtle.clear();
+                for (([x, y], _), color) in balls.iter().zip(colors) {
+                    turtle.teleport(*x, *y, false);
+                    turtle.dot().with_size(30).with_color(color);
+                }
+                turtle.update();
+
+                for ([x, y], [dx, dy]) in balls.iter_mut() {
+                    *x += *dx;
+                    *y += *dy;
+                    if x.abs() > 185. {
+                        *dx = -*dx;
+                    }
+                    if y.abs() > 185. {
+                        *dy = -*dy;
+                    }
+                }
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+        });
+}
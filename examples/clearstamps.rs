@@ -9,7 +9,7 @@ fn main() {
                 println!("stamp id {}", turtle.stamp());
                 turtle.forward(30);
             }
-            turtle.teleport(-100, 100);
+            turtle.teleport(-100, 100, false);
             turtle.right(45);
 
             std::thread::sleep(std::time::Duration::from_millis(500));
@@ -0,0 +1,34 @@
+use slowpoke::*;
+
+// A clock face in logo mode, measuring angles in hours
+fn main() {
+    TurtleArgs::default()
+        .with_size(400, 400)
+        .with_title("clock")
+        .run(|turtle| {
+            turtle.speed("fastest");
+            turtle.mode("logo");
+            turtle.degrees(12);
+
+            for hour in 0..12 {
+                turtle.penup();
+                turtle.home();
+                turtle.setheading(hour);
+                turtle.forward(150);
+                turtle.pendown();
+                turtle.forward(20);
+            }
+
+            // ten past two
+            turtle.penup();
+            turtle.home();
+            turtle.pendown();
+            turtle.penwidth(4);
+            turtle.setheading(2. + 10. / 60.);
+            turtle.forward(80);
+            turtle.home();
+            turtle.penwidth(2);
+            turtle.setheading(2);
+            turtle.forward(130);
+        });
+}
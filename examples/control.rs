@@ -12,13 +12,13 @@ fn main() {
         turtle.fillcolor("red");
         t1.fillcolor("blue");
 
-        turtle.onkeypress(left, 'h');
-        t1.onkeypress(right, 'j');
+        turtle.onkeypress(left, "Left");
+        t1.onkeypress(right, "Right");
 
-        turtle.onkeypress(forward10, 'w');
-        t1.onkeypress(forward30, 's');
+        turtle.onkeypress(forward10, "Up");
+        t1.onkeypress(forward30, 'w');
 
-        turtle.onkeyrelease(right, 'i');
+        turtle.onkeyrelease(right, "space");
         t1.onkeyrelease(left, 'o');
 
         loop {
@@ -27,18 +27,18 @@ fn main() {
     });
 }
 
-fn left(turtle: &mut Turtle, _key: char) {
+fn left(turtle: &mut Turtle, _key: TurtleKey, _mods: KeyModifiers) {
     turtle.left(90.);
 }
 
-fn right(turtle: &mut Turtle, _key: char) {
+fn right(turtle: &mut Turtle, _key: TurtleKey, _mods: KeyModifiers) {
     turtle.right(37);
 }
 
-fn forward30(turtle: &mut Turtle, _key: char) {
-    turtle.forward(30);
+fn forward30(turtle: &mut Turtle, _key: TurtleKey, mods: KeyModifiers) {
+    turtle.forward(if mods.shift { 60 } else { 30 });
 }
 
-fn forward10(turtle: &mut Turtle, _key: char) {
+fn forward10(turtle: &mut Turtle, _key: TurtleKey, _mods: KeyModifiers) {
     turtle.forward(10);
 }
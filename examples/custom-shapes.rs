@@ -0,0 +1,46 @@
+use slowpoke::*;
+
+fn main() {
+    TurtleArgs::default()
+        .with_size(400, 400)
+        .with_title("custom shapes")
+        .run(|turtle| {
+            // record a kite with the pen up, and use it as a shape
+            turtle.penup();
+            turtle.begin_poly();
+            turtle.goto(-20, 8);
+            turtle.goto(-30, 0);
+            turtle.goto(-20, -8);
+            turtle.goto(0, 0);
+            turtle.end_poly();
+            let kite = turtle.get_poly();
+            turtle.register_shape("kite", kite);
+
+            // a little rocket, with a body and two fins in their own colors
+            let mut rocket = Shape::compound();
+            rocket.addcomponent(
+                &[[0., 0.], [-8., 5.], [-24., 5.], [-24., -5.], [-8., -5.]],
+                "light gray",
+                "black",
+            );
+            rocket.addcomponent(&[[-18., 5.], [-26., 11.], [-24., 5.]], "red", "dark red");
+            rocket.addcomponent(&[[-18., -5.], [-26., -11.], [-24., -5.]], "red", "dark red");
+            turtle.register_shape("rocket", rocket);
+
+            turtle.goto(-100, 50);
+            turtle.fillcolor("orange");
+            turtle.shape("kite");
+            for _ in 0..4 {
+                turtle.stamp();
+                turtle.forward(50);
+            }
+
+            turtle.goto(-100, -50);
+            turtle.shape("rocket");
+            for _ in 0..4 {
+                turtle.stamp();
+                turtle.left(30);
+                turtle.forward(50);
+            }
+        });
+}
@@ -0,0 +1,45 @@
+use slowpoke::*;
+
+fn main() {
+    TurtleArgs::default()
+        .with_size(600, 400)
+        .with_title("Dashes, caps and joins")
+        .run(|turtle| {
+            turtle.speed("fast");
+
+            // a grid of dashed lines, like graph paper
+            turtle.pencolor("light blue");
+            turtle.dash(&[6, 4], 0);
+            for x in (-250..=250).step_by(50) {
+                turtle.teleport(x, -150, false);
+                turtle.goto(x, 150);
+            }
+            for y in (-150..=150).step_by(50) {
+                turtle.teleport(-250, y, false);
+                turtle.goto(250, y);
+            }
+
+            // a dotted trail: zero-length dashes with round caps
+            turtle.pencolor("red");
+            turtle.penwidth(12);
+            turtle.linecap("round");
+            turtle.dash(&[0, 12], 0);
+            turtle.teleport(-200, -100, false);
+            turtle.setheading(30);
+            turtle.circle(-150).with_extent(120);
+
+            // solid lines again, with sharp corners
+            turtle.nodash();
+            turtle.pencolor("black");
+            turtle.linecap("square");
+            turtle.linejoin("miter");
+            turtle.teleport(-200, 100, false);
+            turtle.setheading(-60);
+            for _ in 0..3 {
+                turtle.forward(60);
+                turtle.left(120);
+                turtle.forward(60);
+                turtle.right(120);
+            }
+        });
+}
@@ -5,7 +5,7 @@ fn main() {
         .with_size(500, 500)
         .with_title("A Squarish Fractal")
         .run(|turtle| {
-            turtle.tracer(false);
+            turtle.tracer(0, None);
             turtle.penup();
             turtle.goto(-243. / 2., 243. / 2.);
             turtle.pendown();
@@ -13,7 +13,7 @@ fn main() {
                 square_fractal(turtle, 4, 243.);
                 turtle.right(90.);
             }
-            turtle.tracer(true);
+            turtle.tracer(1, None);
         });
 }
 
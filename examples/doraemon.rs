@@ -46,7 +46,7 @@ fn main() {
                 turtle.fillcolor("#ffffff");
                 turtle.begin_fill();
 
-                turtle.tracer(false);
+                turtle.tracer(0, None);
                 let mut a = 2.5;
                 for i in 0..120 {
                     if (0..30).contains(&i) || (60..90).contains(&i) {
@@ -57,7 +57,7 @@ fn main() {
                     turtle.left(3);
                     turtle.forward(a);
                 }
-                turtle.tracer(true);
+                turtle.tracer(1, None);
                 turtle.end_fill();
             }
 
@@ -73,7 +73,7 @@ fn main() {
                 turtle.setheading(215);
                 turtle.circle(120).with_extent(100);
                 turtle.end_fill();
-                turtle.teleport(63.56, 218.24);
+                turtle.teleport(63.56, 218.24, false);
                 turtle.setheading(90);
                 aankha(turtle);
                 turtle.setheading(180);
@@ -164,7 +164,7 @@ fn main() {
             turtle.penwidth(3);
             turtle.speed(9);
             doraemon(turtle);
-            turtle.teleport(100, -300);
+            turtle.teleport(100, -300, false);
             // turtle.write("by CS-SmartKids");
         });
 }
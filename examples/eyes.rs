@@ -9,7 +9,7 @@ fn main() {
                 turtle.fillcolor("#ffffff");
                 turtle.begin_fill();
 
-                turtle.tracer(false);
+                turtle.tracer(0, None);
                 let mut a = 2.5;
                 for i in 0..120 {
                     if (0..30).contains(&i) || (60..90).contains(&i) {
@@ -20,18 +20,18 @@ fn main() {
                     turtle.left(3);
                     turtle.forward(a);
                 }
-                turtle.tracer(true);
+                turtle.tracer(1, None);
                 turtle.end_fill();
             }
 
             turtle.bgcolor("#333333");
-            turtle.teleport(-100, 100);
+            turtle.teleport(-100, 100, false);
             aankha(turtle);
-            turtle.teleport(100, 100);
+            turtle.teleport(100, 100, false);
             aankha(turtle);
-            turtle.teleport(100, -100);
+            turtle.teleport(100, -100, false);
             aankha(turtle);
-            turtle.teleport(-100, -100);
+            turtle.teleport(-100, -100, false);
             aankha(turtle);
         });
 }
@@ -0,0 +1,41 @@
+use slowpoke::*;
+
+fn star(turtle: &mut Turtle, x: f32, y: f32) {
+    turtle.teleport(x, y, false);
+    turtle.begin_fill();
+    for _ in 0..5 {
+        turtle.forward(150);
+        turtle.right(144);
+    }
+    turtle.end_fill();
+}
+
+fn main() {
+    TurtleArgs::default()
+        .with_size(600, 500)
+        .with_title("Fill rules")
+        .run(|turtle| {
+            turtle.speed("fast");
+            turtle.fillcolor("orange");
+
+            // the middle of the star is left empty, as in python...
+            star(turtle, -250., 150.);
+
+            // ...unless the fill rule is "nonzero"
+            turtle.fillrule("nonzero");
+            star(turtle, 50., 150.);
+            turtle.fillrule("evenodd");
+
+            // lifting the pen starts a new part of the fill, so the inner
+            // circle makes a hole
+            turtle.fillcolor("skyblue");
+            turtle.teleport(0, -200, false);
+            turtle.begin_fill();
+            turtle.circle(100);
+            turtle.penup();
+            turtle.goto(0, -150);
+            turtle.pendown();
+            turtle.circle(50);
+            turtle.end_fill();
+        });
+}
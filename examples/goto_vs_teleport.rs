@@ -11,7 +11,7 @@ fn main() {
             println!("press return to teleport");
             let _ = std::io::stdin().read_line(&mut s);
 
-            turtle.teleport(100., -100.);
+            turtle.teleport(100., -100., false);
             println!("press return to goto");
             let _ = std::io::stdin().read_line(&mut s);
 
@@ -19,7 +19,7 @@ fn main() {
             println!("press return to teleport");
             let _ = std::io::stdin().read_line(&mut s);
 
-            turtle.teleport(-100., 100.);
+            turtle.teleport(-100., 100., false);
             println!("press return to goto");
             let _ = std::io::stdin().read_line(&mut s);
 
@@ -27,7 +27,7 @@ fn main() {
             println!("press return to teleport");
             let _ = std::io::stdin().read_line(&mut s);
 
-            turtle.teleport(-50, -50);
+            turtle.teleport(-50, -50, false);
             println!("press return to exit");
             let _ = std::io::stdin().read_line(&mut s);
 
@@ -0,0 +1,65 @@
+use slowpoke::*;
+
+fn square(turtle: &mut Turtle, x: f32, y: f32, size: f32) {
+    turtle.teleport(x, y, false);
+    turtle.begin_fill();
+    for _ in 0..4 {
+        turtle.forward(size);
+        turtle.left(90);
+    }
+    turtle.end_fill();
+}
+
+fn main() {
+    TurtleArgs::default()
+        .with_size(640, 440)
+        .with_title("Gradient and hatch fills")
+        .run(|turtle| {
+            turtle.speed("fast");
+
+            // a sky, from the top of the square to the bottom
+            turtle.fillcolor(
+                Gradient::linear([0., 1.], [0., 0.])
+                    .with_stop(0., "midnight blue")
+                    .with_stop(0.7, "dark orange")
+                    .with_stop(1., "gold"),
+            );
+            square(turtle, -300., 20., 180.);
+
+            // a sun, lit from its upper left
+            turtle.fillcolor(
+                Gradient::radial([0.35, 0.65], 0.7)
+                    .with_stop(0., "light goldenrod yellow")
+                    .with_stop(1., "dark orange"),
+            );
+            turtle.teleport(0., 20., false);
+            turtle.begin_fill();
+            turtle.circle(90);
+            turtle.end_fill();
+
+            // a glow that stays put in the window, whatever is filled with it
+            let glow = Gradient::radial([220., -100.], 120.)
+                .in_world()
+                .with_stop(0., "white")
+                .with_stop(1., "forest green");
+            turtle.fillcolor(glow);
+            square(turtle, 120., -200., 80.);
+            square(turtle, 220., -200., 80.);
+            square(turtle, 170., -110., 80.);
+
+            // hatches
+            turtle.fillcolor(Hatch::diagonal("dark slate gray").with_background("light gray"));
+            square(turtle, 120., 20., 180.);
+            turtle.fillcolor(Hatch::crosshatch("navy").with_spacing(12.).with_width(2.));
+            square(turtle, -300., -200., 150.);
+            turtle.fillcolor(Hatch::dots("brown").with_width(2.).with_background("wheat"));
+            turtle.teleport(-110., -155., false);
+            turtle.begin_fill();
+            for _ in 0..5 {
+                turtle.forward(180);
+                turtle.right(144);
+            }
+            turtle.end_fill();
+            turtle.hideturtle();
+        });
+}
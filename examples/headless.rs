@@ -0,0 +1,27 @@
+use slowpoke::*;
+
+fn main() {
+    let scene = TurtleArgs::default()
+        .with_size(400, 400)
+        .run_headless(|turtle| {
+            turtle.speed("fastest");
+            for _ in 0..4 {
+                turtle.forward(100);
+                turtle.right(90);
+            }
+            turtle.stamp();
+
+            let mut t1 = turtle.hatch();
+            t1.hideturtle();
+            t1.circle(50);
+        });
+
+    for (idx, turtle) in scene.turtles.iter().enumerate() {
+        println!(
+            "turtle {idx}: {} commands, {} stamps, visible: {}",
+            turtle.commands.len(),
+            turtle.stamps.len(),
+            turtle.visible
+        );
+    }
+}
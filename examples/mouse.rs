@@ -5,12 +5,40 @@ fn main() {
         .with_size(400, 400)
         .with_title("a line")
         .run(|turtle| {
-            turtle.onclick(draw_line_to);
-            turtle.ondrag(|_turtle, x, y| println!("drag: {x},{y}"));
-            turtle.onrelease(|_turtle, x, y| println!("release: {x},{y}"));
+            turtle.onscreenclick(draw_line_to, MouseButton::Left);
+            turtle.ondrag(
+                |turtle: &mut Turtle, x, y, _button| turtle.goto(x, y),
+                MouseButton::Left,
+            );
+
+            // handlers can keep state of their own
+            let mut clicks = 0;
+            turtle.onclick(
+                move |turtle: &mut Turtle, _x, _y, _button| {
+                    clicks += 1;
+                    turtle.pencolor(if clicks % 2 == 1 { "red" } else { "black" });
+                },
+                MouseButton::Right,
+            );
+
+            // 'q' stops reporting releases, 'c' stops drawing lines on click
+            let release = turtle.onrelease(
+                |_turtle: &mut Turtle, x, y, button| println!("release {button:?}: {x},{y}"),
+                MouseButton::Left,
+            );
+            turtle.onkey(
+                move |_turtle: &mut Turtle, _key, _mods| release.remove(),
+                'q',
+            );
+            turtle.onkey(
+                |turtle: &mut Turtle, _key, _mods| {
+                    turtle.onscreenclick(None, MouseButton::Left);
+                },
+                'c',
+            );
         });
 }
 
-fn draw_line_to(turtle: &mut Turtle, x: f32, y: f32) {
+fn draw_line_to(turtle: &mut Turtle, x: f32, y: f32, _button: MouseButton) {
     turtle.goto(x, y);
 }
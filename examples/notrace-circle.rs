@@ -8,13 +8,13 @@ fn main() {
             turtle.pencolor("white");
             turtle.bgcolor("grey");
             turtle.speed(1);
-            turtle.tracer(false);
+            turtle.tracer(0, None);
             for i in 3..20 {
                 turtle.circle(10 + i as u32 * 10).with_steps(i);
             }
             for i in 3..20 {
                 turtle.circle(-(10. + i as f64 * 10.)).with_steps(i);
             }
-            turtle.tracer(true);
+            turtle.tracer(1, None);
         });
 }
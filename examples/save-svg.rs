@@ -0,0 +1,28 @@
+use slowpoke::*;
+
+fn main() {
+    let scene = TurtleArgs::default()
+        .with_size(400, 400)
+        .run_headless(|turtle| {
+            turtle.speed("fastest");
+            turtle.bgcolor("light blue");
+            turtle.pencolor("dark green");
+            turtle.penwidth(3);
+            turtle.fillcolor("orange");
+            turtle.begin_fill();
+            for _ in 0..5 {
+                turtle.forward(150);
+                turtle.right(144);
+            }
+            turtle.end_fill();
+            turtle.penup();
+            turtle.goto(-100, -100);
+            turtle.dot().with_size(10).with_color("red");
+            turtle.stamp();
+            turtle.circle(40);
+        });
+
+    scene
+        .save_svg("star.svg")
+        .expect("could not write star.svg");
+}
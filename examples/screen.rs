@@ -0,0 +1,48 @@
+use slowpoke::*;
+
+// The arrow keys steer every turtle at once, "c" wipes the screen and "r"
+// puts everyone back in the middle.
+fn main() {
+    TurtleArgs::default()
+        .with_size(600, 600)
+        .with_title("One screen, many turtles")
+        .run(|turtle| {
+            let colors = ["red", "green", "blue", "orange"];
+            let mut others: Vec<Turtle> = (1..colors.len()).map(|_| turtle.hatch()).collect();
+            for (idx, t) in std::iter::once(&mut *turtle)
+                .chain(others.iter_mut())
+                .enumerate()
+            {
+                t.pencolor(colors[idx]);
+                t.left(90 * idx as i32);
+            }
+
+            let screen = turtle.screen();
+            screen.bgcolor("ivory");
+            screen.onkeypress(|s: &Screen, _key, _mods| steer(s, 0., 20.), "Up");
+            screen.onkeypress(|s: &Screen, _key, _mods| steer(s, 30., 0.), "Left");
+            screen.onkeypress(|s: &Screen, _key, _mods| steer(s, -30., 0.), "Right");
+            screen.onkey(|s: &Screen, _key, _mods| s.clearscreen(), 'c');
+            screen.onkey(|s: &Screen, _key, _mods| s.resetscreen(), 'r');
+
+            loop {
+                std::thread::sleep(std::time::Duration::from_millis(500));
+            }
+        });
+}
+
+fn steer(screen: &Screen, turn: f32, distance: f32) {
+    let handles: Vec<_> = screen
+        .turtles()
+        .into_iter()
+        .map(|mut turtle| {
+            std::thread::spawn(move || {
+                turtle.left(turn);
+                turtle.forward(distance);
+            })
+        })
+        .collect();
+    for handle in handles {
+        let _ = handle.join();
+    }
+}
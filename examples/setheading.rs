@@ -7,7 +7,7 @@ fn main() {
         .run(|turtle| {
             turtle.speed(1);
             turtle.dot().with_size(3).with_color("red");
-            turtle.teleport(10, 20);
+            turtle.teleport(10, 20, false);
             println!("teleported to 10,20; pos = {:?}", turtle.pos());
 
             // draw to the right
@@ -15,7 +15,7 @@ fn main() {
             turtle.forward(100);
             println!("heading 0; forward 100; pos = {:?}", turtle.pos());
 
-            turtle.teleport(10, 20);
+            turtle.teleport(10, 20, false);
             println!("teleported to 10,20; pos = {:?}", turtle.pos());
 
             // draw "south" or down
@@ -23,20 +23,20 @@ fn main() {
             turtle.forward(100);
             println!("heading 270; forward 100; pos = {:?}", turtle.pos());
 
-            turtle.teleport(10, 20);
+            turtle.teleport(10, 20, false);
             println!("teleported to 10,20; pos = {:?}", turtle.pos());
 
             // draw "north" or up
             turtle.setheading(90);
             turtle.forward(100);
             println!("pos = {:?}", turtle.pos());
-            turtle.teleport(10, 20);
+            turtle.teleport(10, 20, false);
 
             // draw "west" or to the left
             turtle.setheading(180);
             turtle.forward(100);
             println!("pos = {:?}", turtle.pos());
-            turtle.teleport(10, 20);
+            turtle.teleport(10, 20, false);
 
             turtle.left(30);
         });
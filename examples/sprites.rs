@@ -0,0 +1,24 @@
+use slowpoke::*;
+
+// An image as the turtle's shape, on a tiled background
+fn main() {
+    TurtleArgs::default()
+        .with_size(400, 400)
+        .with_title("sprites")
+        .run(|turtle| {
+            turtle.bgpic("assets/arrow.png", "tile").unwrap();
+            turtle.bgcolor("light yellow");
+            turtle
+                .register_image_shape("arrow", "assets/arrow.png")
+                .unwrap();
+            turtle.shape("arrow");
+
+            turtle.pencolor("red");
+            turtle.penwidth(3);
+            for _ in 0..8 {
+                turtle.forward(100);
+                turtle.stamp();
+                turtle.left(135);
+            }
+        });
+}
@@ -15,7 +15,7 @@ fn main() {
                 v.push(turtle.stamp());
             }
 
-            turtle.teleport(50, 50);
+            turtle.teleport(50, 50, false);
 
             println!("{v:?}");
 
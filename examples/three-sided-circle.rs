@@ -5,7 +5,7 @@ fn main() {
         .with_size(800, 800)
         .with_title("Circles")
         .run(|turtle| {
-            turtle.teleport(0, -300);
+            turtle.teleport(0, -300, false);
             turtle.speed(1);
             turtle.circle(300).with_steps(3);
         });
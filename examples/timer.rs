@@ -0,0 +1,21 @@
+use slowpoke::*;
+
+fn main() {
+    TurtleArgs::default()
+        .with_size(400, 400)
+        .with_title("timers")
+        .run(|turtle| {
+            turtle.speed("fastest");
+
+            let spiral = turtle.oninterval(step, 100);
+            turtle.ontimer(|turtle| turtle.pencolor("red"), 2000);
+
+            std::thread::sleep(std::time::Duration::from_secs(5));
+            spiral.cancel();
+        });
+}
+
+fn step(turtle: &mut Turtle) {
+    turtle.forward(20);
+    turtle.right(25);
+}
@@ -6,7 +6,7 @@ fn main() {
         .with_title("a triangle circle")
         .run(|turtle| {
             turtle.speed(1);
-            turtle.teleport(0, 180);
+            turtle.teleport(0, 180, false);
             turtle.circle(180).with_steps(3);
         });
 }
@@ -10,6 +10,10 @@ fn main() {
             turtle.forward(50); // draw down
             turtle.undo(); // back up 50 pixels
             turtle.undo(); // turn to the left by 90
+            turtle.redo(); // point down again
+            turtle.redo(); // draw down again
+            turtle.undo(); // back up 50 pixels once more
+            turtle.left(90); // drawing something new means there's nothing left to redo
             turtle.backward(100); // return to origin
             std::process::exit(0);
         });
@@ -0,0 +1,26 @@
+use slowpoke::*;
+
+// Plot a sine wave in world coordinates; try resizing the window
+fn main() {
+    TurtleArgs::default()
+        .with_size(600, 300)
+        .with_title("world coordinates")
+        .run(|turtle| {
+            turtle.speed("fastest");
+            turtle.setworldcoordinates(0, -1.5, 6.3, 1.5);
+
+            turtle.penup();
+            turtle.goto(0, 0);
+            turtle.pendown();
+            turtle.goto(6.3, 0);
+
+            turtle.penup();
+            turtle.goto(0, 0);
+            turtle.pendown();
+            turtle.pencolor("blue");
+            for step in 0..=63 {
+                let x = step as f64 / 10.;
+                turtle.goto(x, x.sin());
+            }
+        });
+}
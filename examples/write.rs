@@ -0,0 +1,37 @@
+use slowpoke::*;
+
+fn main() {
+    TurtleArgs::default()
+        .with_size(400, 300)
+        .with_title("writing")
+        .run(|turtle| {
+            turtle.penup();
+            turtle.goto(-150, 80);
+            turtle
+                .write("left aligned")
+                .with_font(("Arial", 16, "normal"));
+
+            turtle.goto(0, 20);
+            turtle.pencolor("blue");
+            turtle
+                .write("centered, bold")
+                .with_align("center")
+                .with_font(("Times", 20, "bold"));
+
+            turtle.goto(150, -40);
+            turtle.pencolor("dark green");
+            turtle
+                .write("right, underlined")
+                .with_align("right")
+                .with_font(("Courier", 14, "italic underline"));
+
+            turtle.goto(-150, -100);
+            turtle.pendown();
+            turtle.pencolor("red");
+            turtle
+                .write("moved ")
+                .with_move(true)
+                .with_font(("Arial", 16));
+            turtle.write("along").with_font(("Arial", 16));
+        });
+}
@@ -1,62 +1,99 @@
 pub struct ColorNames(&'static str, u8, u8, u8);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum TurtleColor {
     CurrentColor,
-    Color(f32, f32, f32),
+    Color(f32, f32, f32, f32), // red, green, blue, alpha, all from 0 to 1
+    // Numbers as given to `pencolor((r, g, b))`, which are read according to
+    // `colormode()` once they reach the turtle. The alpha is always from 0 to 1.
+    Unscaled(f32, f32, f32, f32),
 }
 
 impl Default for TurtleColor {
     fn default() -> Self {
-        Self::Color(0., 0., 0.)
+        Self::Color(0., 0., 0., 1.)
+    }
+}
+
+impl TurtleColor {
+    // Turn numbers given under `colormode` into a color, or `CurrentColor`
+    // if they are out of range
+    pub(crate) fn resolve(self, colormode: f32) -> Self {
+        match self {
+            TurtleColor::Unscaled(r, g, b, a) => {
+                let in_range = |v: f32| (0. ..=colormode).contains(&v);
+
+                if in_range(r) && in_range(g) && in_range(b) && (0. ..=1.).contains(&a) {
+                    TurtleColor::Color(r / colormode, g / colormode, b / colormode, a)
+                } else {
+                    TurtleColor::CurrentColor
+                }
+            }
+            color => color,
+        }
+    }
+
+    // #rgb, #rgba, #rrggbb or #rrggbbaa
+    fn from_hex(hex: &str) -> Option<Self> {
+        let digits = hex.strip_prefix('#')?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channel = |i: usize, len: usize| {
+            let value = u8::from_str_radix(&digits[i * len..(i + 1) * len], 16).ok()?;
+            // a single digit is repeated, so "f" means "ff"
+            Some(if len == 1 { value * 17 } else { value })
+        };
+
+        let (len, alpha) = match digits.len() {
+            3 => (1, 255),
+            4 => (1, channel(3, 1)?),
+            6 => (2, 255),
+            8 => (2, channel(3, 2)?),
+            _ => return None,
+        };
+
+        Some((channel(0, len)?, channel(1, len)?, channel(2, len)?, alpha).into())
     }
 }
 
 impl From<[f32; 4]> for TurtleColor {
     fn from(color: [f32; 4]) -> TurtleColor {
-        TurtleColor::Color(color[0], color[1], color[2])
+        TurtleColor::Color(color[0], color[1], color[2], color[3])
     }
 }
 
+/// Red, green, blue and alpha from 0 to 1. A color that has yet to reach a
+/// turtle has no `colormode()` to go by, so its numbers are read as if it were
+/// 1.0 and clamped, and `CurrentColor` turns into black, the default pen color.
 impl From<TurtleColor> for [f32; 4] {
     fn from(color: TurtleColor) -> [f32; 4] {
         match color {
-            TurtleColor::CurrentColor => todo!(),
-            TurtleColor::Color(r, g, b) => [r, g, b, 1.],
+            TurtleColor::CurrentColor => [0., 0., 0., 1.],
+            TurtleColor::Color(r, g, b, a) => [r, g, b, a],
+            TurtleColor::Unscaled(r, g, b, a) => [r, g, b, a].map(|v| v.clamp(0., 1.)),
         }
     }
 }
 
-//TODO: use tryfrom instead?
+/// See the conversion to `[f32; 4]` for colors that aren't resolved yet
 impl From<&TurtleColor> for iced::Color {
     fn from(value: &TurtleColor) -> Self {
-        if let TurtleColor::Color(r, g, b) = value {
-            iced::Color {
-                r: *r,
-                g: *g,
-                b: *b,
-                a: 1.,
-            }
-        } else {
-            todo!()
-        }
+        let [r, g, b, a]: [f32; 4] = (*value).into();
+        iced::Color { r, g, b, a }
     }
 }
 
 impl From<&str> for TurtleColor {
     fn from(color_name: &str) -> Self {
         if color_name.starts_with('#') {
-            // #rrggbbaa
-            let value = u32::from_str_radix(&color_name[1..=6], 16)
-                .unwrap_or_else(|_| panic!("Could not parse {color_name} as a hex string"));
-            let r = (value >> 16) & 0xff;
-            let g = (value >> 8) & 0xff;
-            let b = value & 0xff;
-            return TurtleColor::Color(r as f32 / 255., g as f32 / 255., b as f32 / 255.);
+            return TurtleColor::from_hex(color_name)
+                .unwrap_or_else(|| panic!("Could not parse {color_name} as a hex string"));
         }
         for c in &COLOR {
             if color_name == c.0 {
-                return TurtleColor::Color(c.1 as f32 / 255., c.2 as f32 / 255., c.3 as f32 / 255.);
+                return (c.1, c.2, c.3).into();
             }
         }
 
@@ -66,40 +103,47 @@ impl From<&str> for TurtleColor {
 
 impl From<(f64, f64, f64)> for TurtleColor {
     fn from((r, g, b): (f64, f64, f64)) -> Self {
-        fn in_range(v: f64) -> bool {
-            (0. ..=1.).contains(&v)
-        }
+        (r, g, b, 1.).into()
+    }
+}
 
-        if in_range(r) && in_range(g) && in_range(b) {
-            TurtleColor::Color(r as f32, g as f32, b as f32)
-        } else {
-            TurtleColor::CurrentColor
-        }
+impl From<(f64, f64, f64, f64)> for TurtleColor {
+    fn from((r, g, b, a): (f64, f64, f64, f64)) -> Self {
+        TurtleColor::Unscaled(r as f32, g as f32, b as f32, a as f32)
     }
 }
 
 impl From<(f32, f32, f32)> for TurtleColor {
     fn from((r, g, b): (f32, f32, f32)) -> Self {
-        fn in_range(v: f32) -> bool {
-            (0. ..=1.).contains(&v)
-        }
+        (r, g, b, 1.).into()
+    }
+}
 
-        if in_range(r) && in_range(g) && in_range(b) {
-            TurtleColor::Color(r, g, b)
-        } else {
-            TurtleColor::CurrentColor
-        }
+impl From<(f32, f32, f32, f32)> for TurtleColor {
+    fn from((r, g, b, a): (f32, f32, f32, f32)) -> Self {
+        TurtleColor::Unscaled(r, g, b, a)
     }
 }
 
 impl From<(u8, u8, u8)> for TurtleColor {
     fn from((r, g, b): (u8, u8, u8)) -> Self {
-        TurtleColor::Color(r as f32 / 255., g as f32 / 255., b as f32 / 255.)
+        (r, g, b, 255).into()
+    }
+}
+
+impl From<(u8, u8, u8, u8)> for TurtleColor {
+    fn from((r, g, b, a): (u8, u8, u8, u8)) -> Self {
+        TurtleColor::Color(
+            r as f32 / 255.,
+            g as f32 / 255.,
+            b as f32 / 255.,
+            a as f32 / 255.,
+        )
     }
 }
 
 // Color names came from the page at https://www.tcl.tk/man/tcl/TkCmd/colors.htm
-pub const COLOR: [ColorNames; 760] = [
+pub static COLOR: [ColorNames; 760] = [
     ColorNames("alice blue", 240, 248, 255),
     ColorNames("AliceBlue", 240, 248, 255),
     ColorNames("antique white", 250, 235, 215),
@@ -861,3 +905,88 @@ pub const COLOR: [ColorNames; 760] = [
     ColorNames("yellow4", 139, 139, 0),
     ColorNames("YellowGreen", 154, 205, 50),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::TurtleColor;
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(
+            TurtleColor::from("#ff8000"),
+            TurtleColor::from((255u8, 128u8, 0u8))
+        );
+        assert_eq!(
+            TurtleColor::from("#ff800080"),
+            TurtleColor::from((255u8, 128u8, 0u8, 128u8))
+        );
+        assert_eq!(TurtleColor::from("#f80"), TurtleColor::from("#ff8800"));
+        assert_eq!(TurtleColor::from("#f808"), TurtleColor::from("#ff880088"));
+        assert_eq!(TurtleColor::from("#FFF"), TurtleColor::from("white"));
+    }
+
+    #[test]
+    fn bad_hex_colors() {
+        for hex in ["#", "#ff", "#fffff", "#fffffffff", "#ggg", "#+ff"] {
+            assert!(TurtleColor::from_hex(hex).is_none(), "{hex}");
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn bad_hex_names_panic() {
+        let _ = TurtleColor::from("#12345");
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(TurtleColor::from("red"), TurtleColor::Color(1., 0., 0., 1.));
+        assert_eq!(
+            TurtleColor::from("no such color"),
+            TurtleColor::CurrentColor
+        );
+    }
+
+    #[test]
+    fn resolve_by_colormode() {
+        let color = TurtleColor::from((0.5, 1., 0.));
+        assert_eq!(color.resolve(1.), TurtleColor::Color(0.5, 1., 0., 1.));
+        assert_eq!(
+            color.resolve(255.),
+            TurtleColor::Color(0.5 / 255., 1. / 255., 0., 1.)
+        );
+
+        let color = TurtleColor::from((255., 51., 0., 0.5));
+        assert_eq!(color.resolve(255.), TurtleColor::Color(1., 0.2, 0., 0.5));
+        assert_eq!(color.resolve(1.), TurtleColor::CurrentColor);
+    }
+
+    #[test]
+    fn resolve_out_of_range() {
+        assert_eq!(
+            TurtleColor::from((0., 0., -1.)).resolve(1.),
+            TurtleColor::CurrentColor
+        );
+        assert_eq!(
+            TurtleColor::from((0., 0., 0., 2.)).resolve(255.),
+            TurtleColor::CurrentColor
+        );
+        // colors that were never numbers aren't touched
+        assert_eq!(
+            TurtleColor::from("blue").resolve(255.),
+            TurtleColor::from("blue")
+        );
+        assert_eq!(
+            TurtleColor::CurrentColor.resolve(1.),
+            TurtleColor::CurrentColor
+        );
+    }
+
+    #[test]
+    fn unresolved_colors_as_rgba() {
+        let rgba: [f32; 4] = TurtleColor::CurrentColor.into();
+        assert_eq!(rgba, [0., 0., 0., 1.]);
+        let rgba: [f32; 4] = TurtleColor::from((300., 0.5, -1.)).into();
+        assert_eq!(rgba, [1., 0.5, 0., 1.]);
+    }
+}
@@ -1,4 +1,20 @@
-use crate::{color_names::TurtleColor, polygon::TurtleShapeName, speed::TurtleSpeed, Turtle};
+use std::time::Duration;
+
+use crate::{
+    color_names::TurtleColor,
+    coords::TurtleMode,
+    fill::FillStyle,
+    input::{
+        Callback, HandlerHandle, KeyHandler, MouseButton, MouseHandler, ScreenKeyHandler,
+        ScreenMouseHandler, TimerHandle, TimerHandler, TurtleKey,
+    },
+    picture::{BgPicMode, TurtleImage},
+    polygon::{FillRule, Shape, TurtleShapeName},
+    speed::TurtleSpeed,
+    stroke::{LineCap, LineJoin},
+    text::{TextAlign, TurtleFont},
+    PenState,
+};
 
 //
 // A DrawRequest is something that the turtle thread asks us to put on the screen.
@@ -15,17 +31,19 @@ pub enum DrawRequest {
 pub enum TimedDrawCmd {
     Motion(MotionCmd),
     Rotate(RotateCmd),
-    Circle(f32, f32, usize),
+    Circle(f32, Option<f32>, usize), // radius, extent (a full circle if none), steps
     Undo,
+    Redo,
 }
 
 #[derive(Clone, Debug)]
 pub enum MotionCmd {
     Forward(f32),
     GoTo(f32, f32),
-    Teleport(f32, f32),
+    Teleport(f32, f32, bool), // x, y, fill_gap
     SetX(f32),
     SetY(f32),
+    Shift(f32), // along the canvas x axis, in pixels
 }
 
 #[derive(Clone, Debug)]
@@ -41,56 +59,113 @@ pub enum InstantaneousDrawCmd {
     PenUp,
     PenColor(TurtleColor),
     FillColor(TurtleColor),
+    FillStyle(FillStyle), // a gradient or hatch; plain colors are sent as `FillColor`
     PenWidth(f32),
     Dot(Option<f32>, TurtleColor),
     Stamp,
-    Tracer(bool),
+    FullCircle(f32), // angle units in a full turn
     BeginFill,
     EndFill,
     BeginPoly,
     EndPoly,
+    Write(String, TextAlign, TurtleFont, Option<f32>), // and how far to move after, in pixels
+    Pen(PenState),
+    ShapeSize(f32, f32),       // stretch_wid, stretch_len
+    ShapeOutline(Option<f32>), // `None` follows the pen width
+    ShearFactor(f32),
+    Tilt(f32),      // turn the shape by this many angle units
+    TiltAngle(f32), // turn the shape to this many angle units from the heading
+    ShapeTransform([f32; 4]),
+    FillRule(FillRule),
+    Dash(Vec<f32>, f32), // pattern, offset
+    LineCap(LineCap),
+    LineJoin(LineJoin),
+    UndoBuffer(Option<usize>), // `None` turns undo off
+    Clear,                     // rub out the turtle's drawing
+    Reset,                     // and put the turtle back as it started
 }
 
-#[derive(Copy, Clone, Debug)]
-pub enum ScreenCmd {
-    ClearScreen,
-    Background(TurtleColor),
+// commands that change a turtle straight away, rather than in turn with
+// its drawing
+#[derive(Clone, Debug)]
+pub enum TurtleCmd {
     ClearStamp(usize),
     ClearStamps(isize),
     Speed(TurtleSpeed),
     ShowTurtle(bool),
+}
+
+// commands for the window as a whole, sent by a `Screen`
+#[derive(Clone, Debug)]
+pub enum ScreenCmd {
+    ClearScreen,
+    ResetScreen,
+    Background(TurtleColor),
     SetSize([isize; 2]),
+    Mode(TurtleMode),
+    WorldCoordinates([f32; 2], [f32; 2]), // lower left, upper right
+    ColorMode(f32),
+    RegisterShape(String, Shape),
+    BgPic(Option<(TurtleImage, BgPicMode)>),
+    Tracer(usize, Option<u64>), // updates per frame, delay in ms
+    Delay(u64),
+    Update,
+    // a handler of `None` unbinds the event
+    KeyPress(Option<Callback<ScreenKeyHandler>>, TurtleKey, HandlerHandle),
+    KeyRelease(Option<Callback<ScreenKeyHandler>>, TurtleKey, HandlerHandle),
+    ScreenClick(
+        Option<Callback<ScreenMouseHandler>>,
+        MouseButton,
+        HandlerHandle,
+    ),
+    GetScreenSize,
+    GetMode,
+    GetColorMode,
+    GetBgColor,
+    GetTracer,
+    GetDelay,
+    Turtles,
+    Scene,
+    TextInput(String, String), // title, prompt
+    NumInput(String, String),  // title, prompt
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub enum InputCmd {
-    KeyPress(fn(&mut Turtle, char), char),
-    KeyRelease(fn(&mut Turtle, char), char),
-    MousePress(fn(&mut Turtle, x: f32, y: f32)),
-    MouseRelease(fn(&mut Turtle, x: f32, y: f32)),
-    MouseDrag(fn(&mut Turtle, x: f32, y: f32)),
+    // a handler of `None` unbinds the event
+    KeyPress(Option<Callback<KeyHandler>>, TurtleKey, HandlerHandle),
+    KeyRelease(Option<Callback<KeyHandler>>, TurtleKey, HandlerHandle),
+    MousePress(Option<Callback<MouseHandler>>, MouseButton, HandlerHandle),
+    MouseRelease(Option<Callback<MouseHandler>>, MouseButton, HandlerHandle),
+    MouseDrag(Option<Callback<MouseHandler>>, MouseButton, HandlerHandle),
+    ScreenClick(Option<Callback<MouseHandler>>, MouseButton, HandlerHandle),
+    Timer(Callback<TimerHandler>, Duration, bool, TimerHandle), // func, delay, repeat, handle
 }
 
 // Commands which return data
 #[derive(Clone, Debug)]
 pub enum DataCmd {
-    GetScreenSize,
     GetPoly,
     TurtleShape(TurtleShapeName),
     UndoBufferEntries,
+    RedoBufferEntries,
     Towards(f32, f32),
     Position,
     Heading,
     Stamp,
     Visibility,
-    TextInput(String, String), // title, prompt
-    NumInput(String, String),  // title, prompt
+    PenColor,
+    FillColor,
+    Pen,
+    Filling,
+    ShapeTransform,
+    TiltAngle,
 }
 
 #[derive(Clone, Debug)]
 pub(crate) enum Command {
     Draw(DrawRequest),
-    Screen(ScreenCmd),
+    Turtle(TurtleCmd),
     Input(InputCmd),
     Data(DataCmd),
     Hatch,
@@ -98,21 +173,90 @@ pub(crate) enum Command {
 }
 
 impl DrawRequest {
-    pub(crate) fn is_stamp(&self) -> bool {
-        matches!(self, Self::InstantaneousDraw(InstantaneousDrawCmd::Stamp))
+    // read any numeric colors according to `colormode`
+    pub(crate) fn with_colormode(self, colormode: f32) -> Self {
+        match self {
+            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::PenColor(color)) => {
+                DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::PenColor(
+                    color.resolve(colormode),
+                ))
+            }
+            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::FillColor(color)) => {
+                DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::FillColor(
+                    color.resolve(colormode),
+                ))
+            }
+            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::FillStyle(style)) => {
+                DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::FillStyle(
+                    style.resolve(colormode),
+                ))
+            }
+            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Dot(size, color)) => {
+                DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Dot(
+                    size,
+                    color.resolve(colormode),
+                ))
+            }
+            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Pen(state)) => {
+                DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Pen(PenState {
+                    pencolor: state.pencolor.resolve(colormode),
+                    fillcolor: state.fillcolor.resolve(colormode),
+                    ..state
+                }))
+            }
+            cmd => cmd,
+        }
+    }
+
+    // commands that change how the turtle's shape is drawn
+    pub(crate) fn changes_shape(&self) -> bool {
+        matches!(
+            self,
+            Self::InstantaneousDraw(
+                InstantaneousDrawCmd::ShapeSize(..)
+                    | InstantaneousDrawCmd::ShapeOutline(_)
+                    | InstantaneousDrawCmd::ShearFactor(_)
+                    | InstantaneousDrawCmd::Tilt(_)
+                    | InstantaneousDrawCmd::TiltAngle(_)
+                    | InstantaneousDrawCmd::ShapeTransform(_)
+            )
+        )
     }
 
-    pub(crate) fn tracer_true(&self) -> bool {
+    // commands that start or finish a fill, which can't be undone by dropping
+    // the points added since
+    pub(crate) fn restarts_fill(&self) -> bool {
         matches!(
             self,
-            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Tracer(true))
+            Self::InstantaneousDraw(
+                InstantaneousDrawCmd::BeginFill | InstantaneousDrawCmd::EndFill
+            ) | Self::TimedDraw(TimedDrawCmd::Motion(MotionCmd::Teleport(_, _, false)))
         )
     }
 
-    pub(crate) fn tracer_false(&self) -> bool {
+    // and the same for `begin_poly()`
+    pub(crate) fn restarts_poly(&self) -> bool {
         matches!(
             self,
-            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Tracer(false))
+            Self::InstantaneousDraw(InstantaneousDrawCmd::BeginPoly)
         )
     }
+
+    // everything but undo and redo themselves, the settings that python
+    // doesn't undo, and clearing, which forgets what could be undone
+    pub(crate) fn is_undoable(&self) -> bool {
+        !matches!(
+            self,
+            Self::TimedDraw(TimedDrawCmd::Undo | TimedDrawCmd::Redo)
+                | Self::InstantaneousDraw(
+                    InstantaneousDrawCmd::UndoBuffer(_)
+                        | InstantaneousDrawCmd::Clear
+                        | InstantaneousDrawCmd::Reset
+                )
+        )
+    }
+
+    pub(crate) fn is_stamp(&self) -> bool {
+        matches!(self, Self::InstantaneousDraw(InstantaneousDrawCmd::Stamp))
+    }
 }
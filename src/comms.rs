@@ -1,5 +1,7 @@
+use std::sync::mpsc::Sender;
+
 use crate::{
-    command::Command,
+    command::{Command, ScreenCmd},
     turtle::types::{TurtleID, TurtleThread},
 };
 
@@ -8,9 +10,10 @@ pub(crate) enum Response {
     Done,
     Cancel,
     Heading(f32),
-    Position(crate::ScreenPosition<isize>),
+    Position(crate::ScreenPosition<f32>),
     StampID(crate::StampID),
     Turtle(crate::Turtle),
+    Turtles(Vec<crate::Turtle>),
     Count(usize),
     Name(String),
     Polygon(Vec<[f32; 2]>),
@@ -18,18 +21,30 @@ pub(crate) enum Response {
     ScreenSize([isize; 2]),
     TextInput(String),
     NumInput(f32),
+    Scene(crate::Scene),
+    Mode(crate::coords::TurtleMode),
+    ColorMode(f32),
+    Color(crate::color_names::TurtleColor),
+    Pen(crate::PenState),
+    Filling(bool),
+    ShapeTransform(crate::polygon::ShapeTransform),
 }
 
 #[derive(Debug)]
-pub(crate) struct Request {
-    pub(crate) turtle: TurtleID,
-    pub(crate) thread: TurtleThread,
-    pub(crate) cmd: Command,
+pub(crate) enum Request {
+    // answered on the channel belonging to the turtle's thread
+    Turtle {
+        turtle: TurtleID,
+        thread: TurtleThread,
+        cmd: Command,
+    },
+    // a `Screen` isn't tied to any turtle, so it brings its own channel
+    Screen(ScreenCmd, Sender<Response>),
 }
 
 impl Request {
     pub(crate) fn shut_down(turtle: TurtleID, thread: TurtleThread) -> Request {
-        Self {
+        Self::Turtle {
             turtle,
             thread,
             cmd: Command::ShutDown,
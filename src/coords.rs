@@ -0,0 +1,185 @@
+use crate::ScreenPosition;
+
+/// How headings are measured, and whether user coordinates are in pixels,
+/// as in python's `mode()`
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum TurtleMode {
+    #[default]
+    Standard, // 0 points east, positive angles turn counterclockwise
+    Logo,  // 0 points north, positive angles turn clockwise
+    World, // like standard, in the units given to `setworldcoordinates()`
+}
+
+impl From<&str> for TurtleMode {
+    fn from(mode: &str) -> Self {
+        match mode {
+            "logo" => Self::Logo,
+            "world" => Self::World,
+            _ => Self::Standard,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct World {
+    lower_left: [f32; 2],
+    upper_right: [f32; 2],
+    size: [f32; 2], // size of the window (in pixels) when the world was set up
+}
+
+// The canvas is in pixels, with the origin at the center and y pointing down.
+// User coordinates are either the same pixels with y pointing up, or the
+// world set up by `setworldcoordinates()`, which is stretched to fill the
+// window at the time and keeps filling it when the window is resized.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub(crate) struct Coordinates {
+    mode: TurtleMode,
+    world: Option<World>,
+}
+
+impl Coordinates {
+    pub(crate) fn mode(&self) -> TurtleMode {
+        self.mode
+    }
+
+    pub(crate) fn set_mode(&mut self, mode: TurtleMode) {
+        self.mode = mode;
+        if mode != TurtleMode::World {
+            self.world = None;
+        }
+    }
+
+    pub(crate) fn set_world(
+        &mut self,
+        lower_left: [f32; 2],
+        upper_right: [f32; 2],
+        size: [isize; 2],
+    ) {
+        self.mode = TurtleMode::World;
+        self.world = Some(World {
+            lower_left,
+            upper_right,
+            size: [size[0] as f32, size[1] as f32],
+        });
+    }
+
+    // pixels per user unit along each axis
+    pub(crate) fn scale(&self) -> [f32; 2] {
+        self.world.map_or([1., 1.], |world| {
+            [
+                world.size[0] / (world.upper_right[0] - world.lower_left[0]),
+                world.size[1] / (world.upper_right[1] - world.lower_left[1]),
+            ]
+        })
+    }
+
+    fn center(&self) -> [f32; 2] {
+        self.world.map_or([0., 0.], |world| {
+            [
+                (world.lower_left[0] + world.upper_right[0]) / 2.,
+                (world.lower_left[1] + world.upper_right[1]) / 2.,
+            ]
+        })
+    }
+
+    pub(crate) fn canvas_pos(&self, x: f32, y: f32) -> ScreenPosition<f32> {
+        let [sx, sy] = self.scale();
+        let [cx, cy] = self.center();
+        ScreenPosition::new((x - cx) * sx, -(y - cy) * sy)
+    }
+
+    pub(crate) fn user_pos(&self, pos: ScreenPosition<f32>) -> [f32; 2] {
+        let [sx, sy] = self.scale();
+        let [cx, cy] = self.center();
+        [pos.x / sx + cx, -pos.y / sy + cy]
+    }
+
+    // Angles here are in degrees, clockwise from east. A heading in user
+    // coordinates points somewhere else on the canvas when the world is
+    // stretched more in one direction than the other.
+    pub(crate) fn canvas_angle(&self, angle: f32) -> f32 {
+        scale_angle(angle, self.scale())
+    }
+
+    // Convert a standard heading (counterclockwise from east) to the heading
+    // this mode reports, and back again
+    pub(crate) fn heading(&self, standard: f32) -> f32 {
+        self.standard_heading(standard).rem_euclid(360.)
+    }
+
+    pub(crate) fn standard_heading(&self, heading: f32) -> f32 {
+        match self.mode {
+            TurtleMode::Logo => 90. - heading,
+            TurtleMode::Standard | TurtleMode::World => heading,
+        }
+    }
+
+    // How much the canvas has to be stretched to fill a window of `size`
+    pub(crate) fn view(&self, size: [f32; 2]) -> [f32; 2] {
+        self.world.map_or([1., 1.], |world| {
+            [size[0] / world.size[0], size[1] / world.size[1]]
+        })
+    }
+}
+
+// The direction `angle` points in once x and y have been stretched by `scale`.
+// Whole turns are kept, so that animating between two angles goes the same
+// way round before and after.
+pub(crate) fn scale_angle(angle: f32, [sx, sy]: [f32; 2]) -> f32 {
+    if sx == sy {
+        return angle;
+    }
+
+    let turns = (angle / 360.).round() * 360.;
+    let (sin, cos) = (angle - turns).to_radians().sin_cos();
+    (sin * sy).atan2(cos * sx).to_degrees() + turns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logo_headings() {
+        let mut coords = Coordinates::default();
+        coords.set_mode(TurtleMode::Logo);
+
+        assert_eq!(coords.heading(90.), 0.);
+        assert_eq!(coords.heading(0.), 90.);
+        assert_eq!(coords.heading(180.), 270.);
+        assert_eq!(coords.standard_heading(90.), 0.);
+    }
+
+    #[test]
+    fn world_fills_the_window() {
+        let mut coords = Coordinates::default();
+        coords.set_world([0., 0.], [100., 50.], [200, 200]);
+
+        assert_eq!(coords.scale(), [2., 4.]);
+        assert_eq!(coords.canvas_pos(0., 0.), ScreenPosition::new(-100., 100.));
+        assert_eq!(
+            coords.canvas_pos(100., 50.),
+            ScreenPosition::new(100., -100.)
+        );
+        assert_eq!(coords.user_pos(ScreenPosition::new(0., 0.)), [50., 25.]);
+    }
+
+    #[test]
+    fn world_stretches_with_the_window() {
+        let mut coords = Coordinates::default();
+        coords.set_world([-1., -1.], [1., 1.], [200, 100]);
+
+        assert_eq!(coords.view([200., 100.]), [1., 1.]);
+        assert_eq!(coords.view([400., 300.]), [2., 3.]);
+
+        coords.set_mode(TurtleMode::Standard);
+        assert_eq!(coords.view([400., 300.]), [1., 1.]);
+    }
+
+    #[test]
+    fn stretched_angles() {
+        assert_eq!(scale_angle(30., [1., 1.]), 30.);
+        assert!((scale_angle(45., [1., 2.]) - 2f32.atan().to_degrees()).abs() < 1e-4);
+        assert!((scale_angle(405., [1., 2.]) - 360. - 2f32.atan().to_degrees()).abs() < 1e-3);
+    }
+}
@@ -1,55 +1,114 @@
 mod circle;
 mod dot;
+mod write;
+
+use std::{io, path::Path};
 
 use crate::{
     color_names::TurtleColor,
     command::{
-        DataCmd, DrawRequest, InstantaneousDrawCmd, MotionCmd, RotateCmd, ScreenCmd, TimedDrawCmd,
+        DataCmd, DrawRequest, InstantaneousDrawCmd, MotionCmd, RotateCmd, TimedDrawCmd, TurtleCmd,
     },
     comms::Response,
-    polygon::TurtleShapeName,
+    coords::TurtleMode,
+    fill::FillStyle,
+    picture::BgPicMode,
+    polygon::{FillRule, Shape, ShapeTransform, TurtleShapeName},
     speed::TurtleSpeed,
+    stroke::{LineCap, LineJoin},
     turtle::Turtle,
-    ScreenPosition, StampID,
+    PenState, ScreenPosition, StampID,
 };
 
 impl Turtle {
     /*
-     * Screen commands
+     * Screen commands, which go to the screen shared by every turtle
      */
     pub fn bgcolor<C: Into<TurtleColor>>(&mut self, color: C) {
-        self.do_screen(ScreenCmd::Background(color.into()));
+        self.screen().bgcolor(color);
     }
 
+    /// Erase everything and reset every turtle, as `Screen::clearscreen()` does
     pub fn clearscreen(&mut self) {
-        self.do_screen(ScreenCmd::ClearScreen);
+        self.screen().clearscreen();
     }
 
-    pub fn clearstamp(&mut self, id: StampID) {
-        self.do_screen(ScreenCmd::ClearStamp(id));
+    /// Reset every turtle, as `reset()` does
+    pub fn resetscreen(&mut self) {
+        self.screen().resetscreen();
     }
 
-    pub fn speed<S: Into<TurtleSpeed>>(&mut self, speed: S) {
-        self.do_screen(ScreenCmd::Speed(speed.into()));
+    pub fn screensize<S: Into<[isize; 2]>>(&mut self, s: S) {
+        self.screen().screensize(s);
     }
 
-    pub fn showturtle(&mut self) {
-        self.do_screen(ScreenCmd::ShowTurtle(true));
+    /// Switch between "standard", "logo" and "world" coordinates. Unlike python,
+    /// this doesn't reset the turtles.
+    pub fn mode<M: Into<TurtleMode>>(&mut self, mode: M) {
+        self.screen().mode(mode);
     }
 
-    pub fn hideturtle(&mut self) {
-        self.do_screen(ScreenCmd::ShowTurtle(false));
+    /// Map the window onto the rectangle from (`llx`, `lly`) at the lower left
+    /// to (`urx`, `ury`) at the upper right, and switch to world mode.
+    pub fn setworldcoordinates<A, B, C, D>(&mut self, llx: A, lly: B, urx: C, ury: D)
+    where
+        A: Into<f64>,
+        B: Into<f64>,
+        C: Into<f64>,
+        D: Into<f64>,
+    {
+        self.screen().setworldcoordinates(llx, lly, urx, ury);
     }
 
-    pub fn screensize<S: Into<[isize; 2]>>(&mut self, s: S) {
-        self.do_screen(ScreenCmd::SetSize(s.into()));
+    /// Whether numeric colors run up to 1.0 or up to 255, as in python's
+    /// `colormode()`
+    pub fn colormode<N: Into<f64>>(&mut self, colormode: N) {
+        self.screen().colormode(colormode);
+    }
+
+    /// Add a shape that any turtle can switch to with `shape(name)`
+    pub fn register_shape<S: Into<Shape>>(&mut self, name: &str, shape: S) {
+        self.screen().register_shape(name, shape);
+    }
+
+    /// Add a shape drawn from a PNG or GIF file
+    pub fn register_image_shape<P: AsRef<Path>>(&mut self, name: &str, path: P) -> io::Result<()> {
+        self.screen().register_image_shape(name, path)
+    }
+
+    /// Show a PNG or GIF picture behind the drawing, or remove it with "nopic"
+    pub fn bgpic<P: AsRef<Path>, M: Into<BgPicMode>>(
+        &mut self,
+        path: P,
+        placement: M,
+    ) -> io::Result<()> {
+        self.screen().bgpic(path, placement)
+    }
+
+    /*
+     * Turtle commands that take effect straight away
+     */
+    pub fn clearstamp(&mut self, id: StampID) {
+        self.do_turtle(TurtleCmd::ClearStamp(id));
     }
 
     /// Clear a range of stamps. If `which` is 0, clear all stamps; if `which` is < 0, clear
     /// the last `-which` stamps, and if which is > 0, clear the first `which` stamps.
     ///
     pub fn clearstamps(&mut self, which: isize) {
-        self.do_screen(ScreenCmd::ClearStamps(which));
+        self.do_turtle(TurtleCmd::ClearStamps(which));
+    }
+
+    pub fn speed<S: Into<TurtleSpeed>>(&mut self, speed: S) {
+        self.do_turtle(TurtleCmd::Speed(speed.into()));
+    }
+
+    pub fn showturtle(&mut self) {
+        self.do_turtle(TurtleCmd::ShowTurtle(true));
+    }
+
+    pub fn hideturtle(&mut self) {
+        self.do_turtle(TurtleCmd::ShowTurtle(false));
     }
 
     /*
@@ -92,12 +151,38 @@ impl Turtle {
         ));
     }
 
-    pub fn fillcolor<C: Into<TurtleColor>>(&mut self, color: C) {
+    /// Choose how fills started after this treat overlaps and holes:
+    /// "evenodd" (the default, as in python) or "nonzero"
+    pub fn fillrule<R: Into<FillRule>>(&mut self, rule: R) {
         self.do_draw(DrawRequest::InstantaneousDraw(
-            InstantaneousDrawCmd::FillColor(color.into()),
+            InstantaneousDrawCmd::FillRule(rule.into()),
         ));
     }
 
+    /// Set what fills are filled with: a color, as in python, or a
+    /// `Gradient` or `Hatch`. Gradients and hatches don't change the color
+    /// of the turtle itself, or what `getfillcolor()` returns.
+    pub fn fillcolor<F: Into<FillStyle>>(&mut self, fill: F) {
+        let cmd = match fill.into() {
+            FillStyle::Color(color) => InstantaneousDrawCmd::FillColor(color),
+            style => InstantaneousDrawCmd::FillStyle(style),
+        };
+        self.do_draw(DrawRequest::InstantaneousDraw(cmd));
+    }
+
+    /// Set the pen and fill colors together
+    pub fn color<P: Into<TurtleColor>, F: Into<FillStyle>>(&mut self, pen: P, fill: F) {
+        self.pencolor(pen);
+        self.fillcolor(fill);
+    }
+
+    /// Change everything in `state` at once, as in python's `pen(pen=...)`
+    pub fn set_pen(&mut self, state: PenState) {
+        self.do_draw(DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Pen(
+            state,
+        )));
+    }
+
     pub fn penwidth<N: Into<f64>>(&mut self, width: N) {
         let width = width.into() as f32;
         self.do_draw(DrawRequest::InstantaneousDraw(
@@ -105,6 +190,43 @@ impl Turtle {
         ));
     }
 
+    /// Draw dashed lines: dashes of `pattern[0]` pixels, gaps of `pattern[1]`
+    /// and so on, starting `offset` pixels into the pattern. An empty pattern,
+    /// as from `nodash()`, draws solid lines again.
+    ///
+    /// Panics if any length is negative.
+    pub fn dash<N: Copy + Into<f64>, O: Into<f64>>(&mut self, pattern: &[N], offset: O) {
+        let pattern: Vec<f32> = pattern.iter().map(|&len| len.into() as f32).collect();
+        assert!(
+            pattern.iter().all(|len| *len >= 0.),
+            "dash lengths must not be negative"
+        );
+        self.do_draw(DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Dash(
+            pattern,
+            offset.into() as f32,
+        )));
+    }
+
+    /// Draw solid lines again
+    pub fn nodash(&mut self) {
+        self.dash::<f32, _>(&[], 0);
+    }
+
+    /// Finish the ends of lines with "butt" (the default), "round" or "square"
+    pub fn linecap<C: Into<LineCap>>(&mut self, cap: C) {
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::LineCap(cap.into()),
+        ));
+    }
+
+    /// Join lines with "round" corners (the default), or with "miter" or
+    /// "bevel" ones
+    pub fn linejoin<J: Into<LineJoin>>(&mut self, join: J) {
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::LineJoin(join.into()),
+        ));
+    }
+
     pub fn fd<N: Copy + Into<f64>>(&mut self, distance: N) {
         self.forward(distance);
     }
@@ -160,10 +282,25 @@ impl Turtle {
     pub fn setheading<N: Into<f64>>(&mut self, heading: N) {
         let heading = heading.into() as f32;
         self.do_draw(DrawRequest::TimedDraw(TimedDrawCmd::Rotate(
-            RotateCmd::SetHeading(heading - 90.),
+            RotateCmd::SetHeading(heading),
         )));
     }
 
+    /// Measure angles in degrees, with `fullcircle` of them in a full turn
+    /// (python's default is 360)
+    pub fn degrees<N: Into<f64>>(&mut self, fullcircle: N) {
+        let fullcircle = fullcircle.into() as f32;
+        assert!(fullcircle != 0., "a full circle can't be zero degrees");
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::FullCircle(fullcircle),
+        ));
+    }
+
+    /// Measure angles in radians
+    pub fn radians(&mut self) {
+        self.degrees(std::f64::consts::TAU);
+    }
+
     pub fn penup(&mut self) {
         self.do_draw(DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::PenUp));
     }
@@ -174,10 +311,17 @@ impl Turtle {
         ));
     }
 
-    pub fn tracer(&mut self, trace: bool) {
-        self.do_draw(DrawRequest::InstantaneousDraw(
-            InstantaneousDrawCmd::Tracer(trace),
-        ));
+    /// The same as `Screen::tracer()`
+    pub fn tracer<D: Into<Option<u64>>>(&mut self, n: usize, delay: D) {
+        self.screen().tracer(n, delay);
+    }
+
+    pub fn delay(&self, delay: u64) {
+        self.screen().delay(delay);
+    }
+
+    pub fn update(&self) {
+        self.screen().update();
     }
 
     pub fn setpos<X: Into<f64>, Y: Into<f64>>(&mut self, xpos: X, ypos: Y) {
@@ -192,15 +336,19 @@ impl Turtle {
         let x = xpos.into() as f32;
         let y = ypos.into() as f32;
         self.do_draw(DrawRequest::TimedDraw(TimedDrawCmd::Motion(
-            MotionCmd::GoTo(x, -y),
+            MotionCmd::GoTo(x, y),
         )));
     }
 
-    pub fn teleport<X: Into<f64>, Y: Into<f64>>(&mut self, xpos: X, ypos: Y) {
+    /// Jump to (`xpos`, `ypos`) without drawing a line. As in python, a fill
+    /// in progress is finished off and a new one started from there, unless
+    /// `fill_gap` is true, in which case the gap is filled as if the turtle
+    /// had walked it.
+    pub fn teleport<X: Into<f64>, Y: Into<f64>>(&mut self, xpos: X, ypos: Y, fill_gap: bool) {
         let x = xpos.into() as f32;
         let y = ypos.into() as f32;
         self.do_draw(DrawRequest::TimedDraw(TimedDrawCmd::Motion(
-            MotionCmd::Teleport(x, -y),
+            MotionCmd::Teleport(x, y, fill_gap),
         )));
     }
 
@@ -214,7 +362,7 @@ impl Turtle {
     pub fn sety<N: Into<f32>>(&mut self, ypos: N) {
         let y = ypos.into();
         self.do_draw(DrawRequest::TimedDraw(TimedDrawCmd::Motion(
-            MotionCmd::SetY(-y),
+            MotionCmd::SetY(y),
         )));
     }
 
@@ -232,36 +380,80 @@ impl Turtle {
         }
     }
 
+    /// Erase the turtle's drawing and stamps, and forget what can be undone.
+    /// The turtle stays where it is, with the same pen.
+    pub fn clear(&mut self) {
+        self.do_draw(DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Clear));
+    }
+
+    /// Erase the turtle's drawing, as `clear()` does, and put it back in the
+    /// middle of the screen with the pen, speed and shape settings it started
+    /// with. It keeps its shape.
+    pub fn reset(&mut self) {
+        self.do_draw(DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Reset));
+    }
+
+    /// Take back the last thing the turtle did, whether it drew something or
+    /// just changed the pen
     pub fn undo(&mut self) {
         self.do_draw(DrawRequest::TimedDraw(TimedDrawCmd::Undo));
     }
 
+    /// Draw the last command that was undone again, the same way it was drawn
+    /// the first time. Drawing anything new forgets what could be redone.
+    pub fn redo(&mut self) {
+        self.do_draw(DrawRequest::TimedDraw(TimedDrawCmd::Redo));
+    }
+
+    /// Forget what can be undone, and keep up to `size` commands from now on
+    /// (1000 to start with). `None` turns undo off.
+    pub fn setundobuffer<N: Into<Option<usize>>>(&mut self, size: N) {
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::UndoBuffer(size.into()),
+        ));
+    }
+
     /*
      * Info requests
      */
-    pub fn pos(&self) -> ScreenPosition<isize> {
+    pub fn pos(&self) -> ScreenPosition<f32> {
         if let Response::Position(pos) = self.do_data(DataCmd::Position) {
-            [pos.x, -pos.y].into()
+            pos
         } else {
             panic!("invalid response from turtle");
         }
     }
 
-    pub fn position(&self) -> ScreenPosition<isize> {
+    pub fn position(&self) -> ScreenPosition<f32> {
         self.pos()
     }
 
-    pub fn xcor(&self) -> isize {
+    pub fn xcor(&self) -> f32 {
         self.pos().x
     }
 
-    pub fn ycor(&self) -> isize {
+    pub fn ycor(&self) -> f32 {
         self.pos().y
     }
 
-    pub fn distance<D: Into<ScreenPosition<isize>>>(&self, other: D) -> f64 {
+    /// The position rounded to the nearest whole unit
+    pub fn ipos(&self) -> ScreenPosition<isize> {
+        self.pos().round().cast()
+    }
+
+    pub fn ixcor(&self) -> isize {
+        self.ipos().x
+    }
+
+    pub fn iycor(&self) -> isize {
+        self.ipos().y
+    }
+
+    /// The distance to a point, such as `(0, 100)` or `(0.5, 100.)`, or to
+    /// another turtle
+    pub fn distance<D: Into<TurtlePoint>>(&self, other: D) -> f64 {
         let self_pos = self.pos();
-        let other_pos: ScreenPosition<isize> = other.into();
+        let TurtlePoint(other_pos) = other.into();
 
         let dx = (other_pos.x - self_pos.x) as f64;
         let dy = (other_pos.y - self_pos.y) as f64;
@@ -287,6 +479,59 @@ impl Turtle {
         }
     }
 
+    pub fn getmode(&self) -> TurtleMode {
+        self.screen().getmode()
+    }
+
+    pub fn getcolormode(&self) -> f32 {
+        self.screen().getcolormode()
+    }
+
+    pub fn getpencolor(&self) -> TurtleColor {
+        if let Response::Color(color) = self.do_data(DataCmd::PenColor) {
+            color
+        } else {
+            panic!("invalid response from turtle");
+        }
+    }
+
+    pub fn getfillcolor(&self) -> TurtleColor {
+        if let Response::Color(color) = self.do_data(DataCmd::FillColor) {
+            color
+        } else {
+            panic!("invalid response from turtle");
+        }
+    }
+
+    pub fn getbgcolor(&self) -> TurtleColor {
+        self.screen().getbgcolor()
+    }
+
+    pub fn pen(&self) -> PenState {
+        if let Response::Pen(state) = self.do_data(DataCmd::Pen) {
+            state
+        } else {
+            panic!("invalid response from turtle");
+        }
+    }
+
+    pub fn isdown(&self) -> bool {
+        self.pen().pendown
+    }
+
+    pub fn pensize(&self) -> f32 {
+        self.pen().pensize
+    }
+
+    /// Whether `begin_fill()` has been called without a matching `end_fill()`
+    pub fn filling(&self) -> bool {
+        if let Response::Filling(filling) = self.do_data(DataCmd::Filling) {
+            filling
+        } else {
+            panic!("invalid response from turtle");
+        }
+    }
+
     pub fn undobufferentries(&self) -> usize {
         if let Response::Count(count) = self.do_data(DataCmd::UndoBufferEntries) {
             count
@@ -295,6 +540,17 @@ impl Turtle {
         }
     }
 
+    /// How many undone commands `redo()` can draw again
+    pub fn redobufferentries(&self) -> usize {
+        if let Response::Count(count) = self.do_data(DataCmd::RedoBufferEntries) {
+            count
+        } else {
+            panic!("invalid response from turtle");
+        }
+    }
+
+    /// Switch to a built-in or registered shape, and return the name of the
+    /// current one. Unknown names are ignored.
     pub fn shape<S: Into<TurtleShapeName>>(&self, shape: S) -> String {
         if let Response::Name(shape) = self.do_data(DataCmd::TurtleShape(shape.into())) {
             shape
@@ -303,6 +559,8 @@ impl Turtle {
         }
     }
 
+    /// The polygon recorded between `begin_poly()` and `end_poly()`, in user
+    /// coordinates
     pub fn get_poly(&self) -> Vec<[f32; 2]> {
         if let Response::Polygon(polygon) = self.do_data(DataCmd::GetPoly) {
             polygon
@@ -311,6 +569,98 @@ impl Turtle {
         }
     }
 
+    /*
+     * Shape transforms
+     */
+
+    /// Stretch the shape across the heading by `stretch_wid` and along it by
+    /// `stretch_len`
+    pub fn shapesize<W: Into<f64>, L: Into<f64>>(&mut self, stretch_wid: W, stretch_len: L) {
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::ShapeSize(stretch_wid.into() as f32, stretch_len.into() as f32),
+        ));
+    }
+
+    /// Outline the shape `width` pixels wide, instead of with the pen size
+    pub fn shapeoutline<N: Into<f64>>(&mut self, width: N) {
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::ShapeOutline(Some(width.into() as f32)),
+        ));
+    }
+
+    /// The stretch factors and outline width, as in python's `shapesize()`:
+    /// (stretch_wid, stretch_len, outline)
+    pub fn getshapesize(&self) -> (f32, f32, f32) {
+        let transform = self.shape_transform();
+        let [wid, len] = transform.stretch();
+        let outline = transform.outline().unwrap_or_else(|| self.pensize());
+        (wid, len, outline)
+    }
+
+    /// Shear the shape, so that lines along the heading lean by `shear`
+    /// (the tangent of the shear angle)
+    pub fn shearfactor<N: Into<f64>>(&mut self, shear: N) {
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::ShearFactor(shear.into() as f32),
+        ));
+    }
+
+    pub fn getshearfactor(&self) -> f32 {
+        self.shape_transform().shear()
+    }
+
+    /// Turn the shape by `angle` without changing the heading
+    pub fn tilt<N: Into<f64>>(&mut self, angle: N) {
+        self.do_draw(DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Tilt(
+            angle.into() as f32,
+        )));
+    }
+
+    /// Turn the shape to `angle` from the heading, whatever its tilt was
+    pub fn settiltangle<N: Into<f64>>(&mut self, angle: N) {
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::TiltAngle(angle.into() as f32),
+        ));
+    }
+
+    pub fn tiltangle(&self) -> f32 {
+        if let Response::Heading(angle) = self.do_data(DataCmd::TiltAngle) {
+            angle
+        } else {
+            panic!("invalid response from turtle");
+        }
+    }
+
+    /// Transform the shape by the matrix [[t11, t12], [t21, t22]], as in
+    /// python: x is to the turtle's right and y is straight ahead. The
+    /// stretch, shear and tilt are worked out from the matrix.
+    ///
+    /// Panics if the matrix is singular.
+    pub fn shapetransform<N: Into<f64>>(&mut self, t11: N, t12: N, t21: N, t22: N) {
+        let matrix = [t11, t12, t21, t22].map(|t| t.into() as f32);
+        let [m11, m12, m21, m22] = matrix;
+        assert!(
+            m11 * m22 - m12 * m21 != 0.,
+            "bad shape transform matrix: must not be singular"
+        );
+        self.do_draw(DrawRequest::InstantaneousDraw(
+            InstantaneousDrawCmd::ShapeTransform(matrix),
+        ));
+    }
+
+    /// The shape's transform matrix: [t11, t12, t21, t22]
+    pub fn getshapetransform(&self) -> [f32; 4] {
+        self.shape_transform().matrix()
+    }
+
+    fn shape_transform(&self) -> ShapeTransform {
+        if let Response::ShapeTransform(transform) = self.do_data(DataCmd::ShapeTransform) {
+            transform
+        } else {
+            panic!("invalid response from turtle");
+        }
+    }
+
     pub fn isvisible(&self) -> bool {
         if let Response::Visibility(can_see) = self.do_data(DataCmd::Visibility) {
             can_see
@@ -320,12 +670,15 @@ impl Turtle {
     }
 
     pub fn getscreensize(&self) -> [isize; 2] {
-        let response = self.do_data(DataCmd::GetScreenSize);
-        if let Response::ScreenSize(size) = response {
-            size
-        } else {
-            panic!("{}", format!("invalid response from turtle: {response:?}"));
-        }
+        self.screen().getscreensize()
+    }
+
+    pub fn gettracer(&self) -> usize {
+        self.screen().gettracer()
+    }
+
+    pub fn getdelay(&self) -> u64 {
+        self.screen().getdelay()
     }
 
     /*
@@ -333,24 +686,57 @@ impl Turtle {
      */
 
     pub fn textinput(&self, title: &str, prompt: &str) -> Option<String> {
-        match self.do_data(DataCmd::TextInput(title.into(), prompt.into())) {
-            Response::TextInput(string) => Some(string),
-            Response::Cancel => None,
-            bad_response => panic!("invalid response '{bad_response:?}' from turtle"),
-        }
+        self.screen().textinput(title, prompt)
     }
 
     pub fn numinput(&self, title: &str, prompt: &str) -> Option<f32> {
-        match self.do_data(DataCmd::NumInput(title.into(), prompt.into())) {
-            Response::NumInput(num) => Some(num),
-            Response::Cancel => None,
-            bad_response => panic!("invalid response '{bad_response:?}' from turtle"),
-        }
+        self.screen().numinput(title, prompt)
     }
 }
 
-impl From<&Turtle> for ScreenPosition<isize> {
+impl From<&Turtle> for ScreenPosition<f32> {
     fn from(other_turtle: &Turtle) -> Self {
         other_turtle.pos()
     }
 }
+
+impl From<&Turtle> for ScreenPosition<isize> {
+    fn from(other_turtle: &Turtle) -> Self {
+        other_turtle.ipos()
+    }
+}
+
+/// A point for `distance()` to measure to. Integers, floats, positions and
+/// turtles can all be turned into one.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TurtlePoint(pub ScreenPosition<f32>);
+
+impl<X: Into<f64>, Y: Into<f64>> From<(X, Y)> for TurtlePoint {
+    fn from((x, y): (X, Y)) -> Self {
+        Self(ScreenPosition::new(x.into() as f32, y.into() as f32))
+    }
+}
+
+impl<N: Into<f64>> From<[N; 2]> for TurtlePoint {
+    fn from([x, y]: [N; 2]) -> Self {
+        (x, y).into()
+    }
+}
+
+impl From<ScreenPosition<f32>> for TurtlePoint {
+    fn from(pos: ScreenPosition<f32>) -> Self {
+        Self(pos)
+    }
+}
+
+impl From<ScreenPosition<isize>> for TurtlePoint {
+    fn from(pos: ScreenPosition<isize>) -> Self {
+        Self(pos.cast())
+    }
+}
+
+impl From<&Turtle> for TurtlePoint {
+    fn from(other_turtle: &Turtle) -> Self {
+        Self(other_turtle.pos())
+    }
+}
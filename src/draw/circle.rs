@@ -6,16 +6,16 @@ use crate::{
 pub struct TurtleCircle<'a> {
     radius: f64,
     steps: usize,
-    extent: f64,
+    extent: Option<f64>, // a full circle, in whatever units angles are in
     turtle: &'a mut Turtle,
 }
 
 impl Turtle {
-    pub fn circle<R: Into<f64>>(&mut self, radius: R) -> TurtleCircle {
+    pub fn circle<R: Into<f64>>(&mut self, radius: R) -> TurtleCircle<'_> {
         TurtleCircle {
             radius: radius.into(),
             steps: 32,
-            extent: 360.,
+            extent: None,
             turtle: self,
         }
     }
@@ -28,7 +28,7 @@ impl<'a> TurtleCircle<'a> {
     }
 
     pub fn with_extent<E: Into<f64>>(mut self, extent: E) -> TurtleCircle<'a> {
-        self.extent = extent.into();
+        self.extent = Some(extent.into());
         self
     }
 }
@@ -38,8 +38,43 @@ impl<'a> Drop for TurtleCircle<'a> {
         self.turtle
             .do_draw(DrawRequest::TimedDraw(TimedDrawCmd::Circle(
                 self.radius as f32,
-                self.extent as f32,
+                self.extent.map(|extent| extent as f32),
                 self.steps,
             )));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use crate::testing::{assert_at, run};
+
+    #[test]
+    fn full_circle_in_radians() {
+        run(|turtle| {
+            turtle.radians();
+            turtle.circle(50);
+            assert_at(turtle, 0., 0.);
+            assert!(turtle.heading().abs() < 1e-3);
+
+            turtle.circle(50).with_extent(PI);
+            assert_at(turtle, 0., 100.);
+            assert!((turtle.heading() - PI).abs() < 1e-3);
+        });
+    }
+
+    #[test]
+    fn full_circle_in_other_degrees() {
+        run(|turtle| {
+            turtle.degrees(400);
+            turtle.circle(50);
+            assert_at(turtle, 0., 0.);
+            assert!(turtle.heading().abs() < 1e-3);
+
+            turtle.circle(-50).with_extent(100);
+            assert_at(turtle, 50., -50.);
+            assert!((turtle.heading() - 300.).abs() < 1e-3);
+        });
+    }
+}
@@ -11,7 +11,7 @@ pub struct TurtleDot<'a> {
 }
 
 impl Turtle {
-    pub fn dot(&mut self) -> TurtleDot {
+    pub fn dot(&mut self) -> TurtleDot<'_> {
         TurtleDot {
             width: None,
             color: TurtleColor::CurrentColor,
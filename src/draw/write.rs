@@ -0,0 +1,106 @@
+use crate::{
+    command::{DrawRequest, InstantaneousDrawCmd},
+    text::{TextAlign, TurtleFont},
+    Turtle,
+};
+
+pub struct TurtleWrite<'a> {
+    text: String,
+    move_turtle: bool,
+    align: TextAlign,
+    font: TurtleFont,
+    turtle: &'a mut Turtle,
+}
+
+impl Turtle {
+    /// Write text at the turtle's position, in the current pen color
+    pub fn write<S: Into<String>>(&mut self, text: S) -> TurtleWrite<'_> {
+        TurtleWrite {
+            text: text.into(),
+            move_turtle: false,
+            align: TextAlign::default(),
+            font: TurtleFont::default(),
+            turtle: self,
+        }
+    }
+}
+
+impl<'a> TurtleWrite<'a> {
+    /// Move the turtle to the right edge of the text once it's been written
+    pub fn with_move(mut self, move_turtle: bool) -> TurtleWrite<'a> {
+        self.move_turtle = move_turtle;
+        self
+    }
+
+    pub fn with_align<A: Into<TextAlign>>(mut self, align: A) -> TurtleWrite<'a> {
+        self.align = align.into();
+        self
+    }
+
+    pub fn with_font<F: Into<TurtleFont>>(mut self, font: F) -> TurtleWrite<'a> {
+        self.font = font.into();
+        self
+    }
+}
+
+impl<'a> Drop for TurtleWrite<'a> {
+    fn drop(&mut self) {
+        let text = std::mem::take(&mut self.text);
+
+        let end = self.move_turtle.then(|| {
+            let width = self.font.width(&text);
+            match self.align {
+                TextAlign::Left => width,
+                TextAlign::Center => width / 2.,
+                TextAlign::Right => 0.,
+            }
+        });
+
+        self.turtle
+            .do_draw(DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Write(
+                text,
+                self.align,
+                self.font.clone(),
+                end,
+            )));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        generate::DrawCommand,
+        testing::{assert_at, run},
+    };
+
+    fn texts(commands: &[DrawCommand]) -> usize {
+        commands
+            .iter()
+            .filter(|cmd| matches!(cmd, DrawCommand::DrawText(..)))
+            .count()
+    }
+
+    #[test]
+    fn write_and_move_are_undone_together() {
+        let scene = run(|turtle| {
+            turtle.write("hello").with_move(true);
+            assert!(turtle.pos().x > 0.);
+            assert_eq!(turtle.undobufferentries(), 1);
+
+            turtle.undo();
+            assert_at(turtle, 0., 0.);
+        });
+
+        assert_eq!(texts(&scene.turtles[0].commands), 0);
+    }
+
+    #[test]
+    fn write_without_moving() {
+        let scene = run(|turtle| {
+            turtle.write("hello");
+            assert_at(turtle, 0., 0.);
+        });
+
+        assert_eq!(texts(&scene.turtles[0].commands), 1);
+    }
+}
@@ -0,0 +1,373 @@
+use iced::{widget::canvas::Path, Point};
+
+use crate::{color_names::TurtleColor, coords::Coordinates, polygon::TurtlePolygon};
+
+/// What `fillcolor()` fills shapes with: a plain color, a gradient or a hatch
+/// pattern. Anything that can be turned into a color can be used as a plain
+/// fill.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FillStyle {
+    Color(TurtleColor),
+    Gradient(Gradient),
+    Hatch(Hatch),
+}
+
+impl<C: Into<TurtleColor>> From<C> for FillStyle {
+    fn from(color: C) -> Self {
+        Self::Color(color.into())
+    }
+}
+
+impl From<Gradient> for FillStyle {
+    fn from(gradient: Gradient) -> Self {
+        Self::Gradient(gradient)
+    }
+}
+
+impl From<Hatch> for FillStyle {
+    fn from(hatch: Hatch) -> Self {
+        Self::Hatch(hatch)
+    }
+}
+
+impl FillStyle {
+    // read any numeric colors according to `colormode`
+    pub(crate) fn resolve(self, colormode: f32) -> Self {
+        match self {
+            Self::Color(color) => Self::Color(color.resolve(colormode)),
+            Self::Gradient(mut gradient) => {
+                for (_, color) in &mut gradient.stops {
+                    *color = color.resolve(colormode);
+                }
+                Self::Gradient(gradient)
+            }
+            Self::Hatch(mut hatch) => {
+                hatch.color = hatch.color.resolve(colormode);
+                hatch.background = hatch.background.map(|color| color.resolve(colormode));
+                Self::Hatch(hatch)
+            }
+        }
+    }
+
+    // What to fill `polygon` with, once it's been drawn. Plain colors come from
+    // the turtle's fill color instead.
+    pub(crate) fn paint(&self, polygon: &TurtlePolygon, coords: &Coordinates) -> Option<FillPaint> {
+        match self {
+            Self::Color(_) => None,
+            Self::Gradient(gradient) => {
+                gradient.on_canvas(polygon, coords).map(FillPaint::Gradient)
+            }
+            Self::Hatch(hatch) => Some(FillPaint::Hatch(hatch.clone())),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum GradientShape {
+    Linear([f32; 2], [f32; 2]), // start, end
+    Radial([f32; 2], f32),      // center, radius
+}
+
+/// A gradient for `fillcolor()`. Its points are relative to the bounds of
+/// the filled shape, from (0, 0) at the lower left to (1, 1) at the upper
+/// right, with radii measured against the longer side; or, after
+/// `in_world()`, they're in the turtle's own coordinates. Colors beyond the
+/// ends carry on as the first and last stops.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    shape: GradientShape,
+    stops: Vec<(f32, TurtleColor)>, // offset from 0 to 1, color
+    world: bool,
+}
+
+impl Gradient {
+    /// Blend from one color at `start` to another at `end`
+    pub fn linear(start: [f32; 2], end: [f32; 2]) -> Self {
+        Self {
+            shape: GradientShape::Linear(start, end),
+            stops: Vec::new(),
+            world: false,
+        }
+    }
+
+    /// Blend from one color at `center` to another `radius` away
+    pub fn radial(center: [f32; 2], radius: f32) -> Self {
+        Self {
+            shape: GradientShape::Radial(center, radius),
+            stops: Vec::new(),
+            world: false,
+        }
+    }
+
+    /// Add a color `offset` of the way (from 0 to 1) along the gradient
+    pub fn with_stop<C: Into<TurtleColor>>(mut self, offset: f32, color: C) -> Self {
+        let offset = offset.clamp(0., 1.);
+        let index = self.stops.partition_point(|(o, _)| *o <= offset);
+        self.stops.insert(index, (offset, color.into()));
+        self
+    }
+
+    /// Take the points (and radius) as user coordinates, rather than relative
+    /// to the shape being filled
+    pub fn in_world(mut self) -> Self {
+        self.world = true;
+        self
+    }
+
+    fn on_canvas(&self, polygon: &TurtlePolygon, coords: &Coordinates) -> Option<CanvasGradient> {
+        let stops: Vec<_> = self
+            .stops
+            .iter()
+            .filter(|(_, color)| matches!(color, TurtleColor::Color(..)))
+            .copied()
+            .collect();
+        if stops.is_empty() {
+            return None;
+        }
+
+        let [min, max] = polygon.bounds()?;
+        let point = |[x, y]: [f32; 2]| {
+            if self.world {
+                coords.canvas_pos(x, y).to_array()
+            } else {
+                // the canvas y axis points down
+                [
+                    min[0] + x * (max[0] - min[0]),
+                    max[1] - y * (max[1] - min[1]),
+                ]
+            }
+        };
+        let length = |radius: f32| {
+            if self.world {
+                let [sx, sy] = coords.scale();
+                radius * (sx.abs() + sy.abs()) / 2.
+            } else {
+                radius * (max[0] - min[0]).max(max[1] - min[1])
+            }
+        };
+
+        let shape = match self.shape {
+            GradientShape::Linear(start, end) => GradientShape::Linear(point(start), point(end)),
+            GradientShape::Radial(center, radius) => {
+                GradientShape::Radial(point(center), length(radius))
+            }
+        };
+        Some(CanvasGradient { shape, stops })
+    }
+}
+
+/// The marks a `Hatch` fills a shape with
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum HatchKind {
+    #[default]
+    Diagonal, // lines going up to the right
+    Crosshatch, // lines both ways
+    Dots,
+}
+
+impl From<&str> for HatchKind {
+    fn from(kind: &str) -> Self {
+        match kind {
+            "crosshatch" | "cross" => Self::Crosshatch,
+            "dots" | "dotted" => Self::Dots,
+            _ => Self::Diagonal,
+        }
+    }
+}
+
+/// A pattern of lines or dots for `fillcolor()`. The marks line up with the
+/// center of the window, so shapes filled side by side join up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hatch {
+    kind: HatchKind,
+    color: TurtleColor,
+    background: Option<TurtleColor>, // `None` to leave the gaps empty
+    spacing: f32,
+    width: f32,
+}
+
+impl Hatch {
+    pub fn new<K: Into<HatchKind>, C: Into<TurtleColor>>(kind: K, color: C) -> Self {
+        Self {
+            kind: kind.into(),
+            color: color.into(),
+            background: None,
+            spacing: 8.,
+            width: 1.,
+        }
+    }
+
+    pub fn diagonal<C: Into<TurtleColor>>(color: C) -> Self {
+        Self::new(HatchKind::Diagonal, color)
+    }
+
+    pub fn crosshatch<C: Into<TurtleColor>>(color: C) -> Self {
+        Self::new(HatchKind::Crosshatch, color)
+    }
+
+    pub fn dots<C: Into<TurtleColor>>(color: C) -> Self {
+        Self::new(HatchKind::Dots, color)
+    }
+
+    /// Pixels from one line, or dot, to the next (8 by default)
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        assert!(spacing > 0., "hatch spacing must be positive");
+        self.spacing = spacing;
+        self
+    }
+
+    /// The width of the lines, or the radius of the dots, in pixels (1 by default)
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Fill the gaps between the marks with a color
+    pub fn with_background<C: Into<TurtleColor>>(mut self, color: C) -> Self {
+        self.background = Some(color.into());
+        self
+    }
+
+    pub(crate) fn kind(&self) -> HatchKind {
+        self.kind
+    }
+
+    pub(crate) fn color(&self) -> Option<&TurtleColor> {
+        Some(&self.color).filter(|color| matches!(color, TurtleColor::Color(..)))
+    }
+
+    pub(crate) fn background(&self) -> Option<&TurtleColor> {
+        self.background
+            .as_ref()
+            .filter(|color| matches!(color, TurtleColor::Color(..)))
+    }
+
+    pub(crate) fn width(&self) -> f32 {
+        self.width
+    }
+
+    // The lines to stroke, or dots to fill, inside `polygon`
+    pub(crate) fn marks(&self, polygon: &TurtlePolygon) -> Path {
+        let Some([min, max]) = polygon.bounds() else {
+            return Path::new(|_| {});
+        };
+        let spacing = self.spacing;
+        let first = |v: f32| (v / spacing).floor() as i64;
+        let last = |v: f32| (v / spacing).ceil() as i64;
+
+        Path::new(|b| match self.kind {
+            HatchKind::Dots => {
+                for row in first(min[1])..=last(max[1]) {
+                    for col in first(min[0])..=last(max[0]) {
+                        let center = Point::new(col as f32 * spacing, row as f32 * spacing);
+                        if polygon.contains([center.x, center.y].into()) {
+                            b.circle(center, self.width);
+                        }
+                    }
+                }
+            }
+            HatchKind::Diagonal | HatchKind::Crosshatch => {
+                // up to the right is (1, -1) on the canvas, and the lines are
+                // `spacing` apart measured straight across them
+                let step = spacing * std::f32::consts::SQRT_2;
+                let mut families = vec![(-1., min[0] + min[1], max[0] + max[1])];
+                if self.kind == HatchKind::Crosshatch {
+                    families.push((1., min[0] - max[1], max[0] - min[1]));
+                }
+
+                for (dy, lo, hi) in families {
+                    let dir = [1., dy];
+                    for k in (lo / step).floor() as i64..=(hi / step).ceil() as i64 {
+                        // x + y (or x - y) is the same all along the line
+                        let origin = [k as f32 * step, 0.];
+                        for (t0, t1) in polygon.spans(origin, dir) {
+                            b.move_to(Point::new(origin[0] + t0, t0 * dy));
+                            b.line_to(Point::new(origin[0] + t1, t1 * dy));
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+// A gradient with its points worked out on the canvas
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CanvasGradient {
+    shape: GradientShape,
+    stops: Vec<(f32, TurtleColor)>,
+}
+
+impl CanvasGradient {
+    // `Some((start, end))` for a linear gradient
+    pub(crate) fn linear(&self) -> Option<(Point, Point)> {
+        match self.shape {
+            GradientShape::Linear(start, end) => Some((start.into(), end.into())),
+            GradientShape::Radial(..) => None,
+        }
+    }
+
+    // `Some((center, radius))` for a radial gradient
+    pub(crate) fn radial(&self) -> Option<(Point, f32)> {
+        match self.shape {
+            GradientShape::Radial(center, radius) => Some((center.into(), radius)),
+            GradientShape::Linear(..) => None,
+        }
+    }
+
+    pub(crate) fn stops(&self) -> &[(f32, TurtleColor)] {
+        &self.stops
+    }
+
+    // the color `offset` of the way along
+    pub(crate) fn color_at(&self, offset: f32) -> TurtleColor {
+        let index = self.stops.partition_point(|(o, _)| *o <= offset);
+        if index == 0 {
+            return self.stops[0].1;
+        }
+        if index == self.stops.len() {
+            return self.stops[index - 1].1;
+        }
+
+        let (o0, c0) = self.stops[index - 1];
+        let (o1, c1) = self.stops[index];
+        match (c0, c1) {
+            (TurtleColor::Color(r0, g0, b0, a0), TurtleColor::Color(r1, g1, b1, a1)) => {
+                let t = (offset - o0) / (o1 - o0);
+                let mix = |v0: f32, v1: f32| v0 + (v1 - v0) * t;
+                TurtleColor::Color(mix(r0, r1), mix(g0, g1), mix(b0, b1), mix(a0, a1))
+            }
+            _ => c0,
+        }
+    }
+
+    fn scaled(&self, [sx, sy]: [f32; 2]) -> Self {
+        let point = |[x, y]: [f32; 2]| [x * sx, y * sy];
+        let shape = match self.shape {
+            GradientShape::Linear(start, end) => GradientShape::Linear(point(start), point(end)),
+            GradientShape::Radial(center, radius) => {
+                GradientShape::Radial(point(center), radius * (sx + sy) / 2.)
+            }
+        };
+        Self {
+            shape,
+            stops: self.stops.clone(),
+        }
+    }
+}
+
+// A fill that isn't a plain color, kept with the polygon it fills
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FillPaint {
+    Gradient(CanvasGradient),
+    Hatch(Hatch), // marks stay the same size when the window is resized
+}
+
+impl FillPaint {
+    pub(crate) fn scaled(&self, view: [f32; 2]) -> Self {
+        match self {
+            Self::Gradient(gradient) => Self::Gradient(gradient.scaled(view)),
+            Self::Hatch(hatch) => Self::Hatch(hatch.clone()),
+        }
+    }
+}
@@ -1,40 +1,41 @@
-use std::f32::consts::PI;
+use std::sync::Arc;
 
-use lyon_tessellation::{
-    geom::euclid::{default::Point2D, default::Transform2D},
-    math::Angle,
-};
+use lyon_tessellation::geom::euclid::{default::Point2D, default::Vector2D};
 
 use crate::{
     color_names::TurtleColor,
     command::{DrawRequest, InstantaneousDrawCmd, MotionCmd, RotateCmd, TimedDrawCmd},
-    polygon::TurtlePolygon,
+    coords::{scale_angle, Coordinates, TurtleMode},
+    fill::FillStyle,
+    polygon::{FillRule, Shape, ShapeTransform, TurtlePolygon},
+    stroke::{LineCap, LineJoin},
+    text::{TextAlign, TurtleFont},
     ScreenPosition,
 };
 
 #[derive(Debug, Clone)]
-pub(crate) struct LineInfo {
-    pub begin: ScreenPosition<isize>,
-    pub end: ScreenPosition<isize>,
+pub struct LineInfo {
+    pub begin: ScreenPosition<f32>,
+    pub end: ScreenPosition<f32>,
     pub pen_down: bool,
 }
 
 #[derive(Debug, Clone)]
-pub(crate) struct CirclePos {
+pub struct CirclePos {
     pub angle: f32,
-    pub x: isize,
-    pub y: isize,
+    pub x: f32,
+    pub y: f32,
     pub pen_down: bool,
 }
 
 impl CirclePos {
     pub fn get_data(&self) -> (f32, [f32; 2]) {
-        (self.angle, [self.x as f32, self.y as f32])
+        (self.angle, [self.x, self.y])
     }
 }
 
 #[derive(Debug, Clone)]
-pub(crate) enum DrawCommand {
+pub enum DrawCommand {
     Filler,
     Filled(usize),
     BeginFill,
@@ -46,20 +47,108 @@ pub(crate) enum DrawCommand {
     SetPenColor(TurtleColor),
     SetPenWidth(f32),
     SetFillColor(TurtleColor),
+    SetDash(Vec<f32>, f32), // pattern, offset
+    SetLineCap(LineCap),
+    SetLineJoin(LineJoin),
     DrawPolygon(TurtlePolygon),
     SetHeading(f32, f32),
     DrawDot(Point2D<f32>, f32, TurtleColor), // center, radius, color
-    DrawPolyAt(TurtlePolygon, ScreenPosition<f32>, f32), // poly, pos, angle
+    DrawPolyAt(Shape, ScreenPosition<f32>, f32), // shape, pos, angle
     Circle(Vec<CirclePos>),
+    DrawText(ScreenPosition<f32>, String, TextAlign, TurtleFont), // pos, text, alignment, font
 }
 
-#[derive(Debug)]
+impl DrawCommand {
+    // how far the turtle travels (in pixels) and turns (in degrees) while
+    // drawing this command
+    pub(crate) fn motion(&self) -> (f32, f32) {
+        match self {
+            DrawCommand::Line(line) => {
+                let dx = line.end.x - line.begin.x;
+                let dy = line.end.y - line.begin.y;
+                ((dx * dx + dy * dy).sqrt(), 0.)
+            }
+            DrawCommand::SetHeading(start, end) => (0., end - start),
+            DrawCommand::Circle(points) => points.windows(2).fold((0., 0.), |(d, r), p| {
+                let dx = p[1].x - p[0].x;
+                let dy = p[1].y - p[0].y;
+                (
+                    d + (dx * dx + dy * dy).sqrt(),
+                    r + (p[1].angle - p[0].angle).abs(),
+                )
+            }),
+            _ => (0., 0.),
+        }
+    }
+
+    // The same command with its positions stretched by `view`, for drawing on
+    // a canvas that has been resized since the world coordinates were set
+    pub(crate) fn scaled(&self, view: [f32; 2]) -> DrawCommand {
+        let [vx, vy] = view;
+        let point = |p: ScreenPosition<f32>| ScreenPosition::new(p.x * vx, p.y * vy);
+
+        match self {
+            DrawCommand::Line(line) => DrawCommand::Line(LineInfo {
+                begin: point(line.begin),
+                end: point(line.end),
+                pen_down: line.pen_down,
+            }),
+            DrawCommand::SetHeading(start, end) => {
+                DrawCommand::SetHeading(scale_angle(*start, view), scale_angle(*end, view))
+            }
+            DrawCommand::DrawPolygon(polygon) => DrawCommand::DrawPolygon(polygon.scaled(view)),
+            DrawCommand::DrawDot(center, radius, color) => {
+                DrawCommand::DrawDot(Point2D::new(center.x * vx, center.y * vy), *radius, *color)
+            }
+            DrawCommand::DrawPolyAt(shape, pos, angle) => DrawCommand::DrawPolyAt(
+                shape.clone(),
+                ScreenPosition::new(pos.x * vx, pos.y * vy),
+                scale_angle(*angle, view),
+            ),
+            DrawCommand::Circle(points) => DrawCommand::Circle(
+                points
+                    .iter()
+                    .map(|p| {
+                        let pos = point(ScreenPosition::new(p.x, p.y));
+                        CirclePos {
+                            angle: scale_angle(p.angle, view),
+                            x: pos.x,
+                            y: pos.y,
+                            pen_down: p.pen_down,
+                        }
+                    })
+                    .collect(),
+            ),
+            DrawCommand::DrawText(pos, text, align, font) => DrawCommand::DrawText(
+                ScreenPosition::new(pos.x * vx, pos.y * vy),
+                text.clone(),
+                *align,
+                font.clone(),
+            ),
+            cmd => cmd.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub(crate) struct CurrentTurtleState {
-    pub transform: Transform2D<f32>,
-    pub angle: f32,
+    position: ScreenPosition<f32>, // on the canvas
+    angle: f32,                    // degrees clockwise from east, in user coordinates
+    fullcircle: f32,               // angle units in a full turn, as in python's `degrees()`
+    pub(crate) coords: Coordinates,
     pen_down: bool,
     pen_width: f32,
+    pen_color: TurtleColor,
     fill_color: TurtleColor,
+    // the state is copied for every command that can be undone, so the parts
+    // that can be big are shared
+    fill_style: Option<Arc<FillStyle>>, // a gradient or hatch to use instead of `fill_color`
+    shape_transform: ShapeTransform,
+    fill_rule: FillRule,
+    dash: Arc<[f32]>,
+    dash_offset: f32,
+    line_cap: LineCap,
+    line_join: LineJoin,
 }
 
 pub(crate) trait TurtlePosition<T> {
@@ -68,17 +157,7 @@ pub(crate) trait TurtlePosition<T> {
 
 impl TurtlePosition<f32> for CurrentTurtleState {
     fn pos(&self) -> ScreenPosition<f32> {
-        self.transform
-            .transform_point(ScreenPosition::new(0f32, 0f32))
-    }
-}
-
-impl TurtlePosition<isize> for CurrentTurtleState {
-    fn pos(&self) -> ScreenPosition<isize> {
-        let point = self
-            .transform
-            .transform_point(ScreenPosition::new(0f32, 0f32));
-        ScreenPosition::new(point.x as isize, point.y as isize)
+        self.position
     }
 }
 
@@ -86,32 +165,146 @@ impl Default for CurrentTurtleState {
     fn default() -> Self {
         Self {
             pen_down: true,
-            transform: Transform2D::identity(),
+            position: ScreenPosition::new(0., 0.),
             angle: 0.,
+            fullcircle: 360.,
+            coords: Coordinates::default(),
             pen_width: 1.,
+            pen_color: "black".into(),
             fill_color: "black".into(),
+            fill_style: None,
+            shape_transform: ShapeTransform::default(),
+            fill_rule: FillRule::default(),
+            dash: Arc::new([]),
+            dash_offset: 0.,
+            line_cap: LineCap::default(),
+            line_join: LineJoin::default(),
         }
     }
 }
 
 impl CurrentTurtleState {
-    pub fn angle(&self) -> f32 {
-        self.angle
+    // python's `reset()`: back home with the default pen and shape, keeping
+    // the same coordinates and angle units
+    pub(crate) fn reset(&mut self) {
+        *self = Self {
+            position: self.coords.canvas_pos(0., 0.),
+            angle: -self.coords.standard_heading(0.),
+            fullcircle: self.fullcircle,
+            coords: self.coords,
+            ..Self::default()
+        };
+    }
+
+    // The commands that bring a freshly cleared canvas up to date with where
+    // the turtle is, which way it's facing and how its pen draws
+    pub(crate) fn redraw(&self) -> Vec<DrawCommand> {
+        let angle = self.canvas_angle();
+        vec![
+            DrawCommand::Line(LineInfo {
+                begin: self.position,
+                end: self.position,
+                pen_down: false,
+            }),
+            DrawCommand::SetHeading(angle, angle),
+            DrawCommand::SetPenColor(self.pen_color),
+            DrawCommand::SetPenWidth(self.pen_width / 2.),
+            DrawCommand::SetFillColor(self.fill_color),
+            DrawCommand::SetDash(self.dash.to_vec(), self.dash_offset),
+            DrawCommand::SetLineCap(self.line_cap),
+            DrawCommand::SetLineJoin(self.line_join),
+        ]
+    }
+
+    // the direction the turtle is drawn pointing in
+    pub(crate) fn canvas_angle(&self) -> f32 {
+        self.coords.canvas_angle(self.angle)
+    }
+
+    pub(crate) fn pen_down(&self) -> bool {
+        self.pen_down
+    }
+
+    pub(crate) fn pen_width(&self) -> f32 {
+        self.pen_width
+    }
+
+    pub(crate) fn pen_color(&self) -> TurtleColor {
+        self.pen_color
+    }
+
+    pub(crate) fn fill_color(&self) -> TurtleColor {
+        self.fill_color
+    }
+
+    pub(crate) fn fill_style(&self) -> Option<&FillStyle> {
+        self.fill_style.as_deref()
+    }
+
+    pub(crate) fn fill_rule(&self) -> FillRule {
+        self.fill_rule
+    }
+
+    pub(crate) fn shape_transform(&self) -> ShapeTransform {
+        self.shape_transform
     }
 
-    fn get_point(&self) -> ScreenPosition<isize> {
-        let point: ScreenPosition<f32> = self.pos();
-        [point.x.round() as isize, point.y.round() as isize].into()
+    // python's `tiltangle()`: positive angles turn the shape the same way as
+    // `left()` does in standard mode, and as `right()` does in logo mode
+    pub(crate) fn tiltangle(&self) -> f32 {
+        let tilt = -self.shape_transform.tilt().to_degrees() * self.orient();
+        self.to_units(tilt).rem_euclid(self.fullcircle)
     }
 
-    fn get_floatpoint(&self) -> ScreenPosition<f32> {
+    fn to_tilt(&self, angle: f32) -> f32 {
+        (-self.to_degrees(angle) * self.orient()).to_radians()
+    }
+
+    fn orient(&self) -> f32 {
+        if self.coords.mode() == TurtleMode::Logo {
+            -1.
+        } else {
+            1.
+        }
+    }
+
+    pub(crate) fn user_pos(&self) -> [f32; 2] {
+        self.coords.user_pos(self.position)
+    }
+
+    pub(crate) fn heading(&self) -> f32 {
+        self.to_units(self.coords.heading(-self.angle))
+    }
+
+    pub(crate) fn towards(&self, x: f32, y: f32) -> f32 {
+        let [curx, cury] = self.user_pos();
+        let standard = (y - cury).atan2(x - curx).to_degrees();
+        self.to_units(self.coords.heading(standard))
+    }
+
+    fn to_degrees(&self, angle: f32) -> f32 {
+        angle * 360. / self.fullcircle
+    }
+
+    fn to_units(&self, degrees: f32) -> f32 {
+        degrees * self.fullcircle / 360.
+    }
+
+    // move `distance` user units along the current heading
+    fn advance(&mut self, distance: f32) {
+        let [sx, sy] = self.coords.scale();
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+        self.position += Vector2D::new(distance * cos * sx, distance * sin * sy);
+    }
+
+    fn get_point(&self) -> ScreenPosition<f32> {
         self.pos()
     }
 
     fn get_circlepos(&self) -> CirclePos {
         let point = self.get_point();
         CirclePos {
-            angle: self.angle,
+            angle: self.canvas_angle(),
             x: point.x,
             y: point.y,
             pen_down: self.pen_down,
@@ -123,56 +316,48 @@ impl CurrentTurtleState {
             DrawRequest::TimedDraw(td) => match td {
                 TimedDrawCmd::Circle(radius, extent, steps) => {
                     let mut pointlist = vec![self.get_circlepos()];
-                    let rsign = -radius.signum();
+                    let extent = extent.map_or(360., |extent| self.to_degrees(extent));
 
-                    let theta_d = rsign * (extent / (*steps as f32));
-                    let theta_r = rsign * (theta_d * (2. * PI / 360.));
-                    let len = 2. * radius.abs() * (theta_r / 2.).sin();
-
-                    let half_d: Angle = Angle::degrees(theta_d / 2.);
-                    let angle_d = Angle::degrees(theta_d);
+                    // a positive radius puts the center on the turtle's left
+                    let theta = -radius.signum() * (extent / (*steps as f32));
+                    let len =
+                        2. * radius.abs() * ((extent / (*steps as f32)).to_radians() / 2.).sin();
 
                     for s in 0..*steps {
                         if s == 0 {
-                            self.transform = self.transform.pre_rotate(half_d);
-                            self.angle += theta_d / 2.;
+                            self.angle += theta / 2.;
                         } else {
-                            self.transform = self.transform.pre_rotate(angle_d);
-                            self.angle += theta_d;
+                            self.angle += theta;
                         }
 
-                        self.transform = self.transform.pre_translate([len, 0.].into());
+                        self.advance(len);
                         pointlist.push(self.get_circlepos());
                     }
 
-                    self.transform = self.transform.pre_rotate(half_d);
-                    self.angle += theta_d / 2.;
+                    self.angle += theta / 2.;
                     return Some(DrawCommand::Circle(pointlist));
                 }
                 TimedDrawCmd::Motion(motion) => {
                     let begin = self.get_point();
-                    let start = self.get_floatpoint();
-                    let angle = Angle::degrees(self.angle);
 
                     let mut pen_down = self.pen_down;
                     match motion {
-                        MotionCmd::Forward(dist) => {
-                            self.transform = self.transform.pre_translate([*dist, 0.].into());
-                        }
-                        MotionCmd::Teleport(x, y) => {
-                            self.transform = Transform2D::translation(*x, *y).pre_rotate(angle);
+                        MotionCmd::Forward(dist) => self.advance(*dist),
+                        MotionCmd::Teleport(x, y, _) => {
+                            self.position = self.coords.canvas_pos(*x, *y);
                             pen_down = false;
                         }
                         MotionCmd::GoTo(x, y) => {
-                            self.transform = Transform2D::translation(*x, *y).pre_rotate(angle);
+                            self.position = self.coords.canvas_pos(*x, *y);
                         }
                         MotionCmd::SetX(x) => {
-                            self.transform =
-                                Transform2D::translation(*x, start.y).then_rotate(angle);
+                            self.position.x = self.coords.canvas_pos(*x, 0.).x;
                         }
                         MotionCmd::SetY(y) => {
-                            self.transform =
-                                Transform2D::translation(start.x, *y).then_rotate(angle);
+                            self.position.y = self.coords.canvas_pos(0., *y).y;
+                        }
+                        MotionCmd::Shift(dx) => {
+                            self.position.x += dx;
                         }
                     }
                     let end = self.get_point();
@@ -183,44 +368,89 @@ impl CurrentTurtleState {
                     }));
                 }
                 TimedDrawCmd::Rotate(rotation) => {
-                    let start = self.angle;
+                    let start = self.canvas_angle();
                     match rotation {
                         RotateCmd::Right(angle) => {
-                            let radians = Angle::degrees(*angle);
-                            self.transform = self.transform.pre_rotate(radians);
-                            self.angle += angle;
+                            self.angle += self.to_degrees(*angle);
                         }
                         RotateCmd::Left(angle) => {
-                            let radians = Angle::degrees(-*angle);
-                            self.transform = self.transform.pre_rotate(radians);
-                            self.angle -= angle;
+                            self.angle -= self.to_degrees(*angle);
                         }
                         RotateCmd::SetHeading(h) => {
-                            let h = 180. - h;
-                            let radians = Angle::degrees(h - self.angle + 90.);
-                            self.transform = self.transform.pre_rotate(radians);
-                            self.angle = h + 90.;
+                            // turn whichever way round is shorter
+                            let target = -self.coords.standard_heading(self.to_degrees(*h));
+                            self.angle += (target - self.angle + 180.).rem_euclid(360.) - 180.;
                         }
                     }
-                    return Some(DrawCommand::SetHeading(start, self.angle));
+                    return Some(DrawCommand::SetHeading(start, self.canvas_angle()));
                 }
-                TimedDrawCmd::Undo => {}
+                TimedDrawCmd::Undo | TimedDrawCmd::Redo => {}
             },
             DrawRequest::InstantaneousDraw(id) => match id {
-                InstantaneousDrawCmd::Tracer(_) => {}
+                InstantaneousDrawCmd::UndoBuffer(_)
+                | InstantaneousDrawCmd::Clear
+                | InstantaneousDrawCmd::Reset => {}
+                InstantaneousDrawCmd::Pen(_) => {} // split up by the turtle task
+                InstantaneousDrawCmd::ShapeSize(wid, len) => {
+                    self.shape_transform.set_stretch([*wid, *len]);
+                }
+                InstantaneousDrawCmd::ShapeOutline(outline) => {
+                    self.shape_transform.set_outline(*outline);
+                }
+                InstantaneousDrawCmd::ShearFactor(shear) => {
+                    self.shape_transform.set_shear(*shear);
+                }
+                InstantaneousDrawCmd::Tilt(angle) => {
+                    let tilt = self.shape_transform.tilt() + self.to_tilt(*angle);
+                    self.shape_transform.set_tilt(tilt);
+                }
+                InstantaneousDrawCmd::TiltAngle(angle) => {
+                    self.shape_transform.set_tilt(self.to_tilt(*angle));
+                }
+                InstantaneousDrawCmd::ShapeTransform(matrix) => {
+                    self.shape_transform.set_matrix(*matrix);
+                }
+                InstantaneousDrawCmd::FillRule(rule) => {
+                    self.fill_rule = *rule;
+                }
+                InstantaneousDrawCmd::Dash(pattern, offset) => {
+                    self.dash = pattern.as_slice().into();
+                    self.dash_offset = *offset;
+                    return Some(DrawCommand::SetDash(pattern.clone(), *offset));
+                }
+                InstantaneousDrawCmd::LineCap(cap) => {
+                    self.line_cap = *cap;
+                    return Some(DrawCommand::SetLineCap(*cap));
+                }
+                InstantaneousDrawCmd::LineJoin(join) => {
+                    self.line_join = *join;
+                    return Some(DrawCommand::SetLineJoin(*join));
+                }
+                InstantaneousDrawCmd::FullCircle(fullcircle) => {
+                    self.fullcircle = *fullcircle;
+                }
                 InstantaneousDrawCmd::PenDown => {
                     self.pen_down = true;
                 }
                 InstantaneousDrawCmd::PenUp => {
                     self.pen_down = false;
                 }
-                InstantaneousDrawCmd::PenColor(pc) => {
+                // colors that couldn't be read are ignored
+                InstantaneousDrawCmd::PenColor(pc @ TurtleColor::Color(..)) => {
+                    self.pen_color = *pc;
                     return Some(DrawCommand::SetPenColor(*pc));
                 }
-                InstantaneousDrawCmd::FillColor(fc) => {
+                InstantaneousDrawCmd::FillColor(fc @ TurtleColor::Color(..)) => {
+                    self.fill_color = *fc;
+                    self.fill_style = None;
                     return Some(DrawCommand::SetFillColor(*fc));
                 }
+                InstantaneousDrawCmd::FillStyle(style) => {
+                    self.fill_style = Some(Arc::new(style.clone()));
+                }
+                InstantaneousDrawCmd::PenColor(_) | InstantaneousDrawCmd::FillColor(_) => {}
                 InstantaneousDrawCmd::PenWidth(pw) => {
+                    self.pen_width = *pw;
                     return Some(DrawCommand::SetPenWidth(*pw / 2.));
                 }
                 InstantaneousDrawCmd::Dot(size, color) => {
@@ -229,10 +459,10 @@ impl CurrentTurtleState {
                     } else {
                         self.pen_width * 2.
                     };
-                    let point = self.get_floatpoint();
+                    let point = self.get_point();
 
-                    let color = if matches!(color, TurtleColor::CurrentColor) {
-                        self.fill_color
+                    let color = if !matches!(color, TurtleColor::Color(..)) {
+                        self.pen_color
                     } else {
                         *color
                     };
@@ -245,6 +475,15 @@ impl CurrentTurtleState {
                 InstantaneousDrawCmd::EndFill => return Some(DrawCommand::EndFill),
                 InstantaneousDrawCmd::BeginPoly => return Some(DrawCommand::BeginPoly),
                 InstantaneousDrawCmd::EndPoly => return Some(DrawCommand::EndPoly),
+                InstantaneousDrawCmd::Write(text, align, font, _) => {
+                    let point = self.get_point();
+                    return Some(DrawCommand::DrawText(
+                        point,
+                        text.clone(),
+                        *align,
+                        font.clone(),
+                    ));
+                }
             },
         }
         None
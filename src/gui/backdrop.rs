@@ -0,0 +1,141 @@
+use iced::{
+    advanced::{
+        image::{self, FilterMethod, Renderer as _},
+        layout, mouse,
+        renderer::{self, Quad, Renderer as _},
+        widget::Tree,
+        Clipboard, Layout, Shell, Widget,
+    },
+    event, Color, Element, Event, Length, Rectangle, Renderer, Size, Theme,
+};
+
+// The background color, and the background picture as a single image, drawn
+// behind the canvas. The canvas can't draw images itself, and a photo drawn
+// as fills would take a path for every color in it.
+pub(crate) struct Backdrop<'a, Message> {
+    color: Color,
+    image: Option<image::Handle>,
+    content: Element<'a, Message, Theme, Renderer>,
+}
+
+impl<'a, Message> Backdrop<'a, Message> {
+    pub(crate) fn new(
+        color: Color,
+        image: Option<image::Handle>,
+        content: impl Into<Element<'a, Message, Theme, Renderer>>,
+    ) -> Self {
+        Self {
+            color,
+            image,
+            content: content.into(),
+        }
+    }
+}
+
+impl<'a, Message> Widget<Message, Theme, Renderer> for Backdrop<'a, Message> {
+    fn size(&self) -> Size<Length> {
+        self.content.as_widget().size()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.content)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.content));
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.content
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        renderer.fill_quad(
+            Quad {
+                bounds,
+                ..Quad::default()
+            },
+            self.color,
+        );
+        if let Some(image) = &self.image {
+            renderer.draw(image.clone(), FilterMethod::Linear, bounds);
+        }
+
+        // images are drawn after the canvas within a layer, so the canvas
+        // gets a layer of its own
+        renderer.with_layer(bounds, |renderer| {
+            self.content.as_widget().draw(
+                &tree.children[0],
+                renderer,
+                theme,
+                style,
+                layout,
+                cursor,
+                viewport,
+            );
+        });
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+impl<'a, Message: 'a> From<Backdrop<'a, Message>> for Element<'a, Message, Theme, Renderer> {
+    fn from(backdrop: Backdrop<'a, Message>) -> Self {
+        Element::new(backdrop)
+    }
+}
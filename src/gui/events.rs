@@ -1,12 +1,13 @@
+use crate::input::{KeyModifiers, MouseButton, TurtleKey};
+
 #[derive(Debug)]
 pub(crate) enum TurtleEvent {
     WindowResize(u32, u32), // width, height
-    KeyPress(char),
-    KeyRelease(char),
-    MousePosition(f32, f32), // x and y
-    MousePress(f32, f32),    // click-x, click-y
-    MouseRelease(f32, f32),  // click-x, click-y
-    MouseDrag(f32, f32),     // x and y
-    _Timer,
+    KeyPress(TurtleKey, KeyModifiers),
+    KeyRelease(TurtleKey, KeyModifiers),
+    MousePosition(f32, f32),             // x and y
+    MousePress(f32, f32, MouseButton),   // click-x, click-y, button
+    MouseRelease(f32, f32, MouseButton), // click-x, click-y, button
+    MouseDrag(f32, f32, MouseButton),    // x, y, and the button held down
     Unhandled, // TODO: remove this, and implment TryFrom<Iced::Event> for Self
 }
@@ -0,0 +1,577 @@
+use std::{collections::HashMap, sync::mpsc::Sender};
+
+use super::events::TurtleEvent;
+use crate::{
+    color_names::TurtleColor,
+    comms::Response,
+    coords::Coordinates,
+    generate::DrawCommand,
+    gui::TurtleGui,
+    picture::{BgPicMode, TurtleImage},
+    polygon::TurtleShape,
+    scene::{Scene, SceneTurtle},
+    turtle::{types::TurtleID, TurtleFlags, TurtleTask},
+    ScreenPosition,
+};
+
+#[derive(Default)]
+struct HeadlessTurtle {
+    cmds: Vec<DrawCommand>,
+    turtle_shape: TurtleShape,
+    hide_turtle: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct HeadlessGuiInternal {
+    last_id: TurtleID,
+    turtle: HashMap<TurtleID, HeadlessTurtle>,
+    bgcolor: TurtleColor,
+    bgpic: Option<(TurtleImage, BgPicMode)>,
+    winsize: [isize; 2],
+    resize_request: Option<Sender<Response>>,
+    popups: Vec<Sender<Response>>,
+    coords: Coordinates,
+}
+
+impl TurtleGui for HeadlessGuiInternal {
+    fn new_turtle(&mut self) -> TurtleID {
+        let id = self.last_id.get();
+
+        self.turtle.insert(id, HeadlessTurtle::default());
+        id
+    }
+
+    fn set_shape(&mut self, turtle: TurtleID, shape: TurtleShape) {
+        self.turtle
+            .get_mut(&turtle)
+            .expect("missing turtle")
+            .turtle_shape = shape;
+    }
+
+    fn stamp(&mut self, turtle: TurtleID, pos: ScreenPosition<f32>, angle: f32) -> usize {
+        let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
+        turtle.cmds.push(DrawCommand::DrawPolyAt(
+            turtle.turtle_shape.shape.clone(),
+            pos,
+            angle,
+        ));
+        turtle.cmds.len() - 1
+    }
+
+    fn clear_stamp(&mut self, turtle: TurtleID, stamp: usize) {
+        let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
+        assert!(matches!(
+            turtle.cmds[stamp],
+            DrawCommand::DrawPolyAt(_, _, _)
+        ));
+        turtle.cmds[stamp] = DrawCommand::Filler;
+    }
+
+    fn clear(&mut self, turtle: TurtleID) {
+        let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
+        turtle.cmds.clear();
+    }
+
+    fn get_turtle_shape_name(&mut self, turtle: TurtleID) -> String {
+        let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
+        turtle.turtle_shape.name.clone()
+    }
+
+    fn append_command(&mut self, turtle: TurtleID, cmd: DrawCommand) {
+        let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
+        turtle.cmds.push(cmd);
+    }
+
+    fn get_position(&self, turtle: TurtleID) -> usize {
+        self.turtle[&turtle].cmds.len()
+    }
+
+    fn fill_polygon(&mut self, turtle: TurtleID, cmd: DrawCommand, index: usize) {
+        let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
+        turtle.cmds[index] = cmd;
+        turtle.cmds.push(DrawCommand::Filled(index));
+    }
+
+    fn undo(&mut self, _turtle: TurtleID) {}
+
+    fn pop(&mut self, turtle: TurtleID) -> Option<DrawCommand> {
+        let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
+        let cmd = turtle.cmds.pop();
+
+        if let Some(DrawCommand::Filled(index)) = &cmd {
+            turtle.cmds[*index] = DrawCommand::Filler;
+        }
+
+        cmd
+    }
+
+    fn last_command(&self, turtle: TurtleID) -> Option<&DrawCommand> {
+        self.turtle
+            .get(&turtle)
+            .expect("missing turtle")
+            .cmds
+            .last()
+    }
+
+    // there is nobody to answer a popup, so treat it as cancelled
+    fn numinput(&mut self, reply: Sender<Response>, _title: &str, _prompt: &str) {
+        self.popups.push(reply);
+    }
+
+    fn textinput(&mut self, reply: Sender<Response>, _title: &str, _prompt: &str) {
+        self.popups.push(reply);
+    }
+
+    fn bgcolor(&mut self, color: TurtleColor) {
+        self.bgcolor = color;
+    }
+
+    fn bgpic(&mut self, bgpic: Option<(TurtleImage, BgPicMode)>) {
+        self.bgpic = bgpic;
+    }
+
+    fn resize(&mut self, reply: Sender<Response>, width: isize, height: isize) {
+        self.winsize = [width, height];
+        self.resize_request = Some(reply);
+    }
+
+    fn set_visible(&mut self, turtle: TurtleID, visible: bool) {
+        let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
+        turtle.hide_turtle = !visible;
+    }
+
+    fn is_visible(&self, turtle: TurtleID) -> bool {
+        let turtle = self.turtle.get(&turtle).expect("missing turtle");
+        !turtle.hide_turtle
+    }
+
+    fn visible_shape(&self, turtle: TurtleID) -> Option<&TurtleShape> {
+        let turtle = self.turtle.get(&turtle).expect("missing turtle");
+        (!turtle.hide_turtle).then_some(&turtle.turtle_shape)
+    }
+
+    fn set_coordinates(&mut self, coords: Coordinates) {
+        self.coords = coords;
+    }
+
+    fn scene(&self, size: [isize; 2]) -> Scene {
+        let view = self.coords.view([size[0] as f32, size[1] as f32]);
+        let mut ids: Vec<TurtleID> = self.turtle.keys().copied().collect();
+        ids.sort_by_key(|id| id.index());
+
+        let turtles = ids
+            .iter()
+            .map(|id| {
+                let turtle = &self.turtle[id];
+                SceneTurtle::new(
+                    turtle.cmds.iter().map(|cmd| cmd.scaled(view)).collect(),
+                    turtle.turtle_shape.clone(),
+                    !turtle.hide_turtle,
+                )
+            })
+            .collect();
+
+        Scene::new(self.bgcolor, self.bgpic.clone(), size, turtles)
+    }
+}
+
+impl HeadlessGuiInternal {
+    fn new(size: [isize; 2]) -> Self {
+        let mut this = Self {
+            bgcolor: TurtleColor::from("white"),
+            winsize: size,
+            ..Self::default()
+        };
+        let _turtle = this.new_turtle();
+        this
+    }
+}
+
+pub(crate) struct HeadlessGuiFramework;
+
+impl HeadlessGuiFramework {
+    // Drive the turtle task from this thread until the user's closure has
+    // returned and every queued command has been drawn.
+    pub(crate) fn start(mut flags: TurtleFlags) -> Scene {
+        let func = flags.start_func.take();
+        let size = [flags.size[0] as isize, flags.size[1] as isize];
+
+        let mut tt = TurtleTask::new(&mut flags);
+        let mut gui = HeadlessGuiInternal::new(size);
+
+        tt.handle_event(
+            TurtleEvent::WindowResize(size[0] as u32, size[1] as u32),
+            &gui,
+        );
+        let handle = tt.run_turtle(func.unwrap());
+
+        loop {
+            let finished = handle.is_finished();
+
+            // there's nobody watching, so don't spend any time animating
+            tt.tick_by(&mut gui, f32::INFINITY);
+            tt.frame_shown();
+
+            if let Some(reply) = gui.resize_request.take() {
+                let [width, height] = gui.winsize;
+                tt.handle_event(TurtleEvent::WindowResize(width as u32, height as u32), &gui);
+                let _ = reply.send(Response::Done);
+            }
+
+            for reply in gui.popups.drain(..) {
+                let _ = reply.send(Response::Cancel);
+            }
+
+            if finished && tt.is_idle() {
+                break;
+            }
+
+            std::thread::yield_now();
+        }
+
+        if let Err(panic) = handle.join() {
+            std::panic::resume_unwind(panic);
+        }
+
+        gui.scene(gui.winsize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::PI;
+
+    use crate::{
+        color_names::TurtleColor,
+        generate::DrawCommand,
+        picture::BgPicMode,
+        testing::{assert_at, line_ends, lines, pixel, run},
+        PenState, ScreenPosition, Shape,
+    };
+
+    #[test]
+    fn empty_program() {
+        let scene = run(|_| {});
+
+        assert_eq!(scene.size, [200, 200]);
+        assert_eq!(scene.turtles.len(), 1);
+        assert!(scene.turtles[0].visible);
+        assert!(lines(&scene.turtles[0].commands).is_empty());
+    }
+
+    #[test]
+    fn lines_are_recorded() {
+        let scene = run(|turtle| {
+            turtle.forward(50);
+            turtle.penup();
+            turtle.forward(10);
+        });
+
+        let lines = lines(&scene.turtles[0].commands);
+        assert_eq!(
+            lines,
+            [([0., 0.], [50., 0.], true), ([50., 0.], [60., 0.], false)]
+        );
+    }
+
+    #[test]
+    fn every_command_is_drawn_before_returning() {
+        let scene = run(|turtle| {
+            turtle.speed(1);
+            for _ in 0..4 {
+                turtle.forward(100);
+                turtle.right(90);
+            }
+        });
+
+        assert_eq!(lines(&scene.turtles[0].commands).len(), 4);
+    }
+
+    #[test]
+    fn hatched_turtles_follow_in_order() {
+        let scene = run(|turtle| {
+            let mut second = turtle.hatch();
+            second.forward(20);
+            turtle.hideturtle();
+        });
+
+        assert_eq!(scene.turtles.len(), 2);
+        assert!(!scene.turtles[0].visible);
+        assert!(scene.turtles[1].visible);
+        assert!(lines(&scene.turtles[0].commands).is_empty());
+        assert_eq!(lines(&scene.turtles[1].commands).len(), 1);
+    }
+
+    #[test]
+    fn stamps_are_listed() {
+        let scene = run(|turtle| {
+            turtle.stamp();
+            turtle.forward(30);
+            let id = turtle.stamp();
+            turtle.forward(30);
+            turtle.stamp();
+            turtle.clearstamp(id);
+        });
+
+        let turtle = &scene.turtles[0];
+        assert_eq!(turtle.stamps.len(), 2);
+        for &stamp in &turtle.stamps {
+            assert!(matches!(
+                turtle.commands[stamp],
+                DrawCommand::DrawPolyAt(_, _, _)
+            ));
+        }
+    }
+
+    #[test]
+    fn queries_see_commands_that_werent_waited_for() {
+        run(|turtle| {
+            turtle.tracer(0, None);
+            turtle.forward(50);
+            assert_at(turtle, 50., 0.);
+            turtle.left(90);
+            assert_eq!(turtle.heading(), 90.);
+            turtle.penup();
+            assert!(!turtle.isdown());
+        });
+    }
+
+    #[test]
+    fn screen_commands_follow_turtle_commands() {
+        let scene = run(|turtle| {
+            turtle.tracer(0, None);
+            turtle.forward(50);
+            turtle.screen().clearscreen();
+            turtle.update();
+        });
+
+        let drawn = lines(&scene.turtles[0].commands);
+        assert!(drawn.iter().all(|&(_, _, pen_down)| !pen_down), "{drawn:?}");
+    }
+
+    #[test]
+    fn world_coordinates_follow_the_window() {
+        let scene = run(|turtle| {
+            turtle.setworldcoordinates(0, 0, 100, 100);
+            assert_at(turtle, 50., 50.);
+            turtle.goto(100, 100);
+            assert_at(turtle, 100., 100.);
+            turtle.screensize([400, 300]);
+            assert_at(turtle, 100., 100.);
+        });
+
+        assert_eq!(scene.size, [400, 300]);
+        assert_eq!(line_ends(&scene.turtles[0].commands), [[200., -150.]]);
+    }
+
+    #[test]
+    fn logo_headings() {
+        let scene = run(|turtle| {
+            turtle.mode("logo");
+            assert_eq!(turtle.heading(), 90.);
+            turtle.setheading(0);
+            turtle.forward(50);
+            turtle.right(90);
+            assert_eq!(turtle.heading(), 90.);
+            turtle.forward(20);
+            assert_at(turtle, 20., 50.);
+        });
+
+        let ends: Vec<_> = line_ends(&scene.turtles[0].commands)
+            .iter()
+            .map(|end| end.map(f32::round))
+            .collect();
+        assert_eq!(ends, [[0., -50.], [20., -50.]]);
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "{actual} isn't {expected}"
+        );
+    }
+
+    #[test]
+    fn angles_in_radians() {
+        let scene = run(|turtle| {
+            turtle.radians();
+            turtle.left(PI / 2.);
+            assert_close(turtle.heading(), PI / 2.);
+            turtle.forward(50);
+            assert_at(turtle, 0., 50.);
+            assert_close(turtle.towards(50, 50), 0.);
+            turtle.setheading(PI);
+            assert_close(turtle.heading(), PI);
+            turtle.degrees(360);
+            assert_close(turtle.heading(), 180.);
+        });
+
+        assert_eq!(lines(&scene.turtles[0].commands).len(), 1);
+    }
+
+    #[test]
+    fn angles_in_other_degrees() {
+        run(|turtle| {
+            turtle.degrees(400);
+            turtle.right(100);
+            assert_close(turtle.heading(), 300.);
+            turtle.forward(10);
+            assert_at(turtle, 0., -10.);
+            assert_close(turtle.towards(-10, -10), 200.);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "a full circle can't be zero degrees")]
+    fn zero_degree_circles() {
+        run(|turtle| turtle.degrees(0));
+    }
+
+    #[test]
+    fn positions_keep_their_fractions() {
+        let scene = run(|turtle| {
+            turtle.forward(10.25);
+            turtle.left(90);
+            turtle.forward(0.5);
+            assert_at(turtle, 10.25, 0.5);
+            assert_close(turtle.xcor(), 10.25);
+            assert_close(turtle.ycor(), 0.5);
+            assert_eq!(turtle.ipos(), ScreenPosition::new(10, 1));
+        });
+
+        let ends = line_ends(&scene.turtles[0].commands);
+        assert_close(ends[0][0], 10.25);
+        assert_close(ends[1][1], -0.5);
+    }
+
+    #[test]
+    fn distances_to_points_and_turtles() {
+        run(|turtle| {
+            let mut other = turtle.hatch();
+            other.penup();
+            other.goto(30, 40);
+
+            assert_close(turtle.distance((3, 4)) as f32, 5.);
+            assert_close(turtle.distance((0.5, 0.)) as f32, 0.5);
+            assert_close(turtle.distance([0, -2]) as f32, 2.);
+            assert_close(turtle.distance(other.pos()) as f32, 50.);
+            assert_close(turtle.distance(&other) as f32, 50.);
+        });
+    }
+
+    #[test]
+    fn pen_state_round_trip() {
+        run(|turtle| {
+            assert_eq!(turtle.pen(), PenState::default());
+
+            turtle.color("red", "blue");
+            turtle.penwidth(3);
+            turtle.penup();
+            turtle.hideturtle();
+            let saved = turtle.pen();
+            assert_eq!(saved.pencolor, TurtleColor::from("red"));
+            assert_eq!(saved.fillcolor, TurtleColor::from("blue"));
+            assert_eq!(turtle.pensize(), 3.);
+            assert!(!turtle.isdown());
+            assert!(!saved.shown);
+
+            turtle.set_pen(PenState::default());
+            assert_eq!(turtle.pen(), PenState::default());
+            assert!(turtle.isvisible());
+
+            turtle.set_pen(saved);
+            assert_eq!(turtle.pen(), saved);
+            assert_eq!(turtle.getpencolor(), TurtleColor::from("red"));
+            assert_eq!(turtle.getfillcolor(), TurtleColor::from("blue"));
+        });
+    }
+
+    #[test]
+    fn recorded_polygons_become_shapes() {
+        let scene = run(|turtle| {
+            turtle.begin_poly();
+            turtle.forward(20);
+            turtle.left(90);
+            turtle.forward(20);
+            turtle.end_poly();
+
+            let poly = turtle.get_poly();
+            // end_poly() closes the polygon back at its start
+            assert_eq!(poly.len(), 4, "{poly:?}");
+            assert_close(poly[2][0], 20.);
+            assert_close(poly[2][1], 20.);
+            assert_eq!(poly[3], poly[0]);
+
+            turtle.register_shape("wedge", poly);
+            assert_eq!(turtle.shape("wedge"), "wedge");
+            assert_eq!(turtle.shape("no such shape"), "wedge");
+            turtle.stamp();
+        });
+
+        let turtle = &scene.turtles[0];
+        assert_eq!(turtle.shape, "wedge");
+        assert_eq!(turtle.stamps.len(), 1);
+    }
+
+    #[test]
+    fn compound_shapes_keep_their_colors() {
+        let scene = run(|turtle| {
+            let mut shape = Shape::compound();
+            shape.addcomponent(
+                &[[-10., -10.], [10., -10.], [10., 10.], [-10., 10.]],
+                "red",
+                "red",
+            );
+            turtle.register_shape("square", shape);
+            turtle.shape("square");
+            turtle.fillcolor("blue");
+            turtle.penup();
+            turtle.goto(50, 50);
+            turtle.stamp();
+            turtle.home();
+            turtle.hideturtle();
+        });
+
+        assert_eq!(pixel(&scene, 50, -50), [255, 0, 0, 255]);
+        assert_eq!(pixel(&scene, 0, 0), [255, 255, 255, 255]);
+    }
+
+    const ARROW: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/assets/arrow.png");
+
+    #[test]
+    fn background_pictures() {
+        let blank = run(|turtle| turtle.hideturtle());
+        let scene = run(|turtle| {
+            turtle.hideturtle();
+            turtle.bgpic(ARROW, "stretch").unwrap();
+        });
+
+        assert!(matches!(scene.bgpic, Some((_, BgPicMode::Stretched))));
+        assert_ne!(scene.to_rgba(), blank.to_rgba());
+
+        let scene = run(|turtle| {
+            turtle.bgpic(ARROW, "tile").unwrap();
+            turtle.bgpic("nopic", "tile").unwrap();
+            assert!(turtle.bgpic("no such picture.png", "tile").is_err());
+        });
+        assert!(scene.bgpic.is_none());
+    }
+
+    #[test]
+    fn image_shapes() {
+        let blank = run(|turtle| turtle.hideturtle());
+        let scene = run(|turtle| {
+            turtle.register_image_shape("arrow", ARROW).unwrap();
+            assert_eq!(turtle.shape("arrow"), "arrow");
+            turtle.stamp();
+            turtle.hideturtle();
+            assert!(turtle
+                .register_image_shape("none", "no such picture.png")
+                .is_err());
+        });
+
+        assert_eq!(scene.turtles[0].shape, "arrow");
+        assert_eq!(scene.turtles[0].stamps.len(), 1);
+        assert_ne!(scene.to_rgba(), blank.to_rgba());
+    }
+}
@@ -1,11 +1,12 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::mpsc::Sender};
 
 use iced::{
+    advanced::image,
     event, executor, mouse,
     multi_window::Application,
     widget::{
         button,
-        canvas::{self, fill::Rule, stroke, Cache, Fill, Frame, LineJoin, Path, Stroke},
+        canvas::{self, fill::Rule, gradient::Linear, stroke, Cache, Fill, Frame, Path, Stroke},
         column, container, horizontal_space, row, text, text_input, vertical_space, Canvas,
         TextInput,
     },
@@ -13,22 +14,28 @@ use iced::{
     Color, Element, Event, Length, Point, Rectangle, Renderer, Settings, Size, Subscription, Theme,
 };
 
-use iced::keyboard::{Event::KeyPressed, Event::KeyReleased, Key};
+use iced::keyboard::{key::Named, Event::KeyPressed, Event::KeyReleased, Key, Modifiers};
 use iced::window::Event::Resized;
 
-use either::Either;
-use lyon_tessellation::geom::{euclid::default::Transform2D, Angle};
+use lyon_tessellation::{
+    geom::{euclid::default::Transform2D, Angle},
+    path::Event as PathEvent,
+};
 
-use super::{events::TurtleEvent, StampCount};
+use super::events::TurtleEvent;
 use crate::{
     color_names::TurtleColor,
+    comms::Response,
+    coords::Coordinates,
+    fill::{CanvasGradient, FillPaint, HatchKind},
     generate::DrawCommand,
-    gui::{popup::PopupData, TurtleGui},
-    polygon::TurtleShape,
-    turtle::{
-        types::{TurtleID, TurtleThread},
-        TurtleFlags, TurtleTask,
-    },
+    gui::{backdrop::Backdrop, popup::PopupData, TurtleGui},
+    input::{KeyModifiers, MouseButton, TurtleKey},
+    picture::{self, BgPicMode, TurtleImage},
+    polygon::{FillRule, Shape, TurtleShape},
+    scene::{Scene, SceneTurtle},
+    stroke::{Dash, LineCap, LineJoin, StrokeStyle},
+    turtle::{types::TurtleID, TurtleFlags, TurtleTask},
     ScreenPosition,
 };
 
@@ -44,8 +51,11 @@ pub(crate) enum Message {
 
 #[derive(Debug)]
 pub(crate) enum IcedDrawCmd {
-    Stroke(Path, Color, f32),
-    Fill(Path, Color),
+    Stroke(Path, Color, f32, StrokeStyle),
+    Fill(Path, Color, FillRule),
+    Gradient(Path, CanvasGradient, FillRule),
+    Text(canvas::Text),
+    Image(TurtleImage, Transform2D<f32>), // from the image's top left corner to the canvas
 }
 
 #[derive(Default)]
@@ -61,179 +71,413 @@ impl IndividualTurtle {
     fn draw(&self, frame: &mut Frame) {
         for draw_iced_cmd in &self.drawing {
             match draw_iced_cmd {
-                IcedDrawCmd::Stroke(path, pencolor, penwidth) => frame.stroke(
+                IcedDrawCmd::Stroke(path, pencolor, penwidth, style) => frame.stroke(
                     path,
                     Stroke {
                         style: stroke::Style::Solid(*pencolor),
                         width: *penwidth,
-                        line_join: LineJoin::Round,
+                        line_cap: match style.cap {
+                            LineCap::Butt => stroke::LineCap::Butt,
+                            LineCap::Round => stroke::LineCap::Round,
+                            LineCap::Square => stroke::LineCap::Square,
+                        },
+                        line_join: match style.join {
+                            LineJoin::Miter => stroke::LineJoin::Miter,
+                            LineJoin::Round => stroke::LineJoin::Round,
+                            LineJoin::Bevel => stroke::LineJoin::Bevel,
+                        },
                         ..Stroke::default()
                     },
                 ),
-                IcedDrawCmd::Fill(path, fillcolor) => frame.fill(
+                IcedDrawCmd::Fill(path, fillcolor, rule) => frame.fill(
                     path,
                     Fill {
                         style: stroke::Style::Solid(*fillcolor),
-                        rule: Rule::EvenOdd,
+                        rule: match rule {
+                            FillRule::EvenOdd => Rule::EvenOdd,
+                            FillRule::NonZero => Rule::NonZero,
+                        },
                     },
                 ),
+                IcedDrawCmd::Gradient(path, gradient, rule) => {
+                    draw_gradient(frame, path, gradient, *rule)
+                }
+                IcedDrawCmd::Text(text) => frame.fill_text(text.clone()),
+                IcedDrawCmd::Image(image, transform) => draw_image(frame, image, transform),
             }
         }
     }
 
-    fn convert(&mut self, pct: f32) {
-        fn make_path(path: &mut Vec<(bool, Point)>) -> Path {
-            Path::new(|b| {
-                b.move_to(path[0].1);
-                for (pen, pos) in path.drain(1..) {
-                    if pen {
-                        b.line_to(pos);
-                    } else {
-                        b.move_to(pos);
-                    }
+    fn convert(&mut self, pct: f32, view: [f32; 2]) {
+        self.drawing =
+            convert_commands(&self.cmds, pct, view, &self.turtle_shape, self.hide_turtle);
+    }
+}
+
+// Turn a turtle's draw commands into the paths to stroke and fill. The last
+// command is only drawn `pct` of the way through, to animate it, and the
+// positions are stretched by `view` (see `Coordinates::view`).
+pub(crate) fn convert_commands(
+    cmds: &[DrawCommand],
+    pct: f32,
+    view: [f32; 2],
+    turtle_shape: &TurtleShape,
+    hide_turtle: bool,
+) -> Vec<IcedDrawCmd> {
+    fn make_path(path: &mut Vec<(bool, Point)>) -> Path {
+        Path::new(|b| {
+            b.move_to(path[0].1);
+            for (pen, pos) in path.drain(1..) {
+                if pen {
+                    b.line_to(pos);
+                } else {
+                    b.move_to(pos);
                 }
-                path.clear(); // remove first element
-            })
+            }
+            path.clear(); // remove first element
+        })
+    }
+
+    let mut pencolor = Color::BLACK;
+    let mut penwidth = 1.0;
+    let mut style = StrokeStyle::default();
+    let mut dash = Dash::default();
+    let mut fillcolor = Color::BLACK;
+
+    let mut tpos = [0f32, 0f32];
+    let mut trot = 0f32;
+
+    let mut drawing = Vec::new();
+
+    let mut iter = cmds.iter().peekable();
+    let mut cur_path: Vec<(bool, Point)> = Vec::new();
+
+    while let Some(element) = iter.next() {
+        let last_element = iter.peek().is_none() && pct < 1.;
+        let scaled;
+        let element = if view == [1., 1.] {
+            element
+        } else {
+            scaled = element.scaled(view);
+            &scaled
+        };
+        if !matches!(element, DrawCommand::Line(..))
+            && !matches!(element, DrawCommand::SetHeading(..))
+            && !cur_path.is_empty()
+        {
+            drawing.push(IcedDrawCmd::Stroke(
+                dash.apply(&make_path(&mut cur_path)),
+                pencolor,
+                penwidth,
+                style,
+            ));
         }
 
-        let mut pencolor = Color::BLACK;
-        let mut penwidth = 1.0;
-        let mut fillcolor = Color::BLACK;
-
-        let mut tpos = [0f32, 0f32];
-        let mut trot = 0f32;
-
-        self.drawing.clear();
-
-        let mut iter = self.cmds.iter().peekable();
-        let mut cur_path: Vec<(bool, Point)> = Vec::new();
-
-        while let Some(element) = iter.next() {
-            let last_element = iter.peek().is_none() && pct < 1.;
-            if !matches!(element, DrawCommand::Line(..))
-                && !matches!(element, DrawCommand::SetHeading(..))
-                && !cur_path.is_empty()
-            {
-                self.drawing.push(IcedDrawCmd::Stroke(
-                    make_path(&mut cur_path),
-                    pencolor,
-                    penwidth,
-                ));
+        match element {
+            DrawCommand::Line(l) => {
+                let start: Point = [l.begin.x, l.begin.y].into();
+                let end: Point = if last_element {
+                    let endx = l.begin.x + (l.end.x - l.begin.x) * pct;
+                    let endy = l.begin.y + (l.end.y - l.begin.y) * pct;
+                    tpos = [endx, endy];
+                    [endx, endy]
+                } else {
+                    tpos = [l.end.x, l.end.y];
+                    [l.end.x, l.end.y]
+                }
+                .into();
+                if cur_path.is_empty() {
+                    cur_path.push((l.pen_down, start));
+                }
+                cur_path.push((l.pen_down, end));
             }
-
-            match element {
-                DrawCommand::Line(l) => {
-                    let start: Point = [l.begin.x as f32, l.begin.y as f32].into();
-                    let end: Point = if last_element {
-                        let endx = l.begin.x as f32 + (l.end.x - l.begin.x) as f32 * pct;
-                        let endy = l.begin.y as f32 + (l.end.y - l.begin.y) as f32 * pct;
-                        tpos = [endx, endy];
-                        [endx, endy]
-                    } else {
-                        tpos = [l.end.x as f32, l.end.y as f32];
-                        [l.end.x as f32, l.end.y as f32]
+            DrawCommand::SetPenColor(pc) => {
+                pencolor = pc.into();
+            }
+            DrawCommand::SetPenWidth(pw) => penwidth = *pw,
+            DrawCommand::SetDash(pattern, offset) => dash = Dash::new(pattern, *offset),
+            DrawCommand::SetLineCap(cap) => style.cap = *cap,
+            DrawCommand::SetLineJoin(join) => style.join = *join,
+            DrawCommand::SetFillColor(fc) => {
+                fillcolor = fc.into();
+            }
+            DrawCommand::DrawPolygon(p) => {
+                let path = p.get_path().clone();
+                match p.paint() {
+                    None => drawing.push(IcedDrawCmd::Fill(path, fillcolor, p.rule())),
+                    Some(FillPaint::Gradient(gradient)) => {
+                        drawing.push(IcedDrawCmd::Gradient(path, gradient.clone(), p.rule()));
                     }
-                    .into();
-                    if cur_path.is_empty() {
-                        cur_path.push((l.pen_down, start));
+                    Some(FillPaint::Hatch(hatch)) => {
+                        if let Some(background) = hatch.background() {
+                            drawing.push(IcedDrawCmd::Fill(path, background.into(), p.rule()));
+                        }
+                        if let Some(color) = hatch.color() {
+                            let marks = hatch.marks(p);
+                            drawing.push(if hatch.kind() == HatchKind::Dots {
+                                IcedDrawCmd::Fill(marks, color.into(), FillRule::NonZero)
+                            } else {
+                                let style = StrokeStyle::default();
+                                IcedDrawCmd::Stroke(marks, color.into(), hatch.width(), style)
+                            });
+                        }
                     }
-                    cur_path.push((l.pen_down, end));
-                }
-                DrawCommand::SetPenColor(pc) => {
-                    pencolor = pc.into();
                 }
-                DrawCommand::SetPenWidth(pw) => penwidth = *pw,
-                DrawCommand::SetFillColor(fc) => {
-                    fillcolor = fc.into();
-                }
-                DrawCommand::DrawPolygon(p) => {
-                    self.drawing
-                        .push(IcedDrawCmd::Fill(p.get_path().clone(), fillcolor));
+            }
+            DrawCommand::SetHeading(start, end) => {
+                let rotation = if last_element {
+                    *start + (*end - *start) * pct
+                } else {
+                    *end
+                };
+                trot = rotation;
+            }
+            DrawCommand::DrawDot(center, radius, color) => {
+                let center: Point = Point::new(center.x, center.y);
+                let circle = Path::circle(center, *radius);
+                drawing.push(IcedDrawCmd::Fill(circle, color.into(), FillRule::EvenOdd));
+            }
+            DrawCommand::DrawText(pos, text, align, font) => {
+                let pos = Point::new(pos.x, pos.y);
+                drawing.push(IcedDrawCmd::Text(font.text(text, pos, pencolor, *align)));
+                if font.underline {
+                    let (start, end, width) = font.underline(text, pos, *align);
+                    let underline = Path::line(start, end);
+                    let style = StrokeStyle::default();
+                    drawing.push(IcedDrawCmd::Stroke(underline, pencolor, width, style));
                 }
-                DrawCommand::SetHeading(start, end) => {
-                    let rotation = if last_element {
-                        *start + (*end - *start) * pct
+            }
+            DrawCommand::DrawPolyAt(shape, pos, angle) => {
+                let angle = Angle::degrees(*angle);
+                let xform = Transform2D::rotation(angle).then_translate([pos.x, pos.y].into());
+                draw_shape(&mut drawing, shape, &xform, pencolor, fillcolor, penwidth);
+            }
+            DrawCommand::Circle(points) => {
+                if points[0].pen_down {
+                    let (total, subpercent) = if last_element {
+                        let partial = (points.len() - 1) as f32 * pct;
+                        (partial.floor() as usize, (partial - partial.floor()))
                     } else {
-                        *end
+                        (points.len() - 1, 1_f32)
                     };
-                    trot = rotation;
-                }
-                DrawCommand::DrawDot(center, radius, color) => {
-                    let center: Point = Point::new(center.x, center.y);
-                    let circle = Path::circle(center, *radius);
-                    self.drawing.push(IcedDrawCmd::Fill(circle, color.into()));
-                }
-                DrawCommand::DrawPolyAt(polygon, pos, angle) => {
-                    let path = polygon.get_path();
-                    let angle = Angle::degrees(*angle);
-                    let xform = Transform2D::rotation(angle).then_translate([pos.x, pos.y].into());
-                    let path = path.transform(&xform);
-                    self.drawing
-                        .push(IcedDrawCmd::Fill(path.clone(), fillcolor));
-                    self.drawing
-                        .push(IcedDrawCmd::Stroke(path, pencolor, penwidth));
-                }
-                DrawCommand::Circle(points) => {
-                    if points[0].pen_down {
-                        let (total, subpercent) = if last_element {
-                            let partial = (points.len() - 1) as f32 * pct;
-                            (partial.floor() as usize, (partial - partial.floor()))
-                        } else {
-                            (points.len() - 1, 1_f32)
-                        };
-                        let path = Path::new(|b| {
-                            let (_, start) = points[0].get_data();
-
-                            b.move_to(start.into());
-
-                            let mut iter = points.windows(2).take(total + 1).peekable();
-                            while let Some(p) = iter.next() {
-                                let (end_angle, end) = p[1].get_data();
-                                let last_segment = iter.peek().is_none();
-                                tpos = end;
-                                if last_element && last_segment {
-                                    let (_, begin) = p[0].get_data();
-                                    let endx = begin[0] + (end[0] - begin[0]) * subpercent;
-                                    let endy = begin[1] + (end[1] - begin[1]) * subpercent;
-                                    tpos = [endx, endy];
-                                }
-                                b.line_to(tpos.into());
-                                trot = end_angle;
+                    let path = Path::new(|b| {
+                        let (_, start) = points[0].get_data();
+
+                        b.move_to(start.into());
+
+                        let mut iter = points.windows(2).take(total + 1).peekable();
+                        while let Some(p) = iter.next() {
+                            let (end_angle, end) = p[1].get_data();
+                            let last_segment = iter.peek().is_none();
+                            tpos = end;
+                            if last_element && last_segment {
+                                let (_, begin) = p[0].get_data();
+                                let endx = begin[0] + (end[0] - begin[0]) * subpercent;
+                                let endy = begin[1] + (end[1] - begin[1]) * subpercent;
+                                tpos = [endx, endy];
                             }
-                        });
+                            b.line_to(tpos.into());
+                            trot = end_angle;
+                        }
+                    });
 
-                        self.drawing
-                            .push(IcedDrawCmd::Stroke(path, pencolor, penwidth));
-                    }
+                    let path = dash.apply(&path);
+                    drawing.push(IcedDrawCmd::Stroke(path, pencolor, penwidth, style));
                 }
-                DrawCommand::Filler | DrawCommand::Filled(_) => {}
-                DrawCommand::StampTurtle
-                | DrawCommand::BeginFill
-                | DrawCommand::EndFill
-                | DrawCommand::BeginPoly
-                | DrawCommand::EndPoly => panic!("invalid draw command in gui"),
             }
+            DrawCommand::Filler | DrawCommand::Filled(_) => {}
+            DrawCommand::StampTurtle
+            | DrawCommand::BeginFill
+            | DrawCommand::EndFill
+            | DrawCommand::BeginPoly
+            | DrawCommand::EndPoly => panic!("invalid draw command in gui"),
         }
+    }
 
-        if !cur_path.is_empty() {
-            self.drawing.push(IcedDrawCmd::Stroke(
-                make_path(&mut cur_path),
-                pencolor,
-                penwidth,
-            ));
+    if !cur_path.is_empty() {
+        drawing.push(IcedDrawCmd::Stroke(
+            dash.apply(&make_path(&mut cur_path)),
+            pencolor,
+            penwidth,
+            style,
+        ));
+    }
+
+    if !hide_turtle {
+        let angle = Angle::degrees(trot);
+        let transform = Transform2D::rotation(angle).then_translate(tpos.into());
+        draw_shape(
+            &mut drawing,
+            &turtle_shape.shape,
+            &transform,
+            pencolor,
+            fillcolor,
+            penwidth,
+        );
+    }
+
+    drawing
+}
+
+fn draw_image(frame: &mut Frame, image: &TurtleImage, transform: &Transform2D<f32>) {
+    if let Some((image, [x, y])) = image.transformed(transform) {
+        frame.with_save(|frame| {
+            frame.translate([x, y].into());
+            for (path, color) in image.fills() {
+                frame.fill(path, *color);
+            }
+        });
+    }
+}
+
+fn draw_gradient(frame: &mut Frame, path: &Path, gradient: &CanvasGradient, rule: FillRule) {
+    let rule = match rule {
+        FillRule::EvenOdd => Rule::EvenOdd,
+        FillRule::NonZero => Rule::NonZero,
+    };
+
+    if let Some((start, end)) = gradient.linear() {
+        // the canvas only takes 8 stops, so any more are sampled evenly
+        let stops = gradient.stops();
+        let mut linear = Linear::new(start, end);
+        if stops.len() <= 8 {
+            for (offset, color) in stops {
+                linear = linear.add_stop(*offset, color.into());
+            }
+        } else {
+            for i in 0..8 {
+                let offset = i as f32 / 7.;
+                linear = linear.add_stop(offset, (&gradient.color_at(offset)).into());
+            }
         }
+        let style = stroke::Style::Gradient(linear.into());
+        frame.fill(path, Fill { style, rule });
+    } else if let Some((center, radius)) = gradient.radial() {
+        // There are no radial gradients on the canvas, so draw smaller and
+        // smaller discs of the shape, each on top of the last
+        let color = (&gradient.color_at(1.)).into();
+        frame.fill(
+            path,
+            Fill {
+                style: stroke::Style::Solid(color),
+                rule,
+            },
+        );
+
+        let rings = (radius / 2.).ceil().clamp(2., 96.) as usize;
+        for ring in (1..=rings).rev() {
+            let part = ring as f32 / rings as f32;
+            let disc = clip_to_circle(path, center, radius * part);
+            let color = (&gradient.color_at(part - 0.5 / rings as f32)).into();
+            frame.fill(
+                &disc,
+                Fill {
+                    style: stroke::Style::Solid(color),
+                    rule,
+                },
+            );
+        }
+    }
+}
 
-        if !self.hide_turtle {
-            let path = self.turtle_shape.shape.get_path();
-            let angle = Angle::degrees(trot);
-            let transform = Transform2D::rotation(angle).then_translate(tpos.into());
-            let path = path.transform(&transform);
-            self.drawing
-                .push(IcedDrawCmd::Fill(path.clone(), fillcolor));
-            self.drawing
-                .push(IcedDrawCmd::Stroke(path, pencolor, penwidth));
+// The parts of `path` inside a circle, clipping each subpath on its own so
+// that what's inside the circle winds the same way as before
+fn clip_to_circle(path: &Path, center: Point, radius: f32) -> Path {
+    const SIDES: usize = 64;
+    let corners: Vec<Point> = (0..SIDES)
+        .map(|i| {
+            let angle = i as f32 * std::f32::consts::TAU / SIDES as f32;
+            Point::new(
+                center.x + radius * angle.cos(),
+                center.y + radius * angle.sin(),
+            )
+        })
+        .collect();
+
+    let mut subpaths = Vec::new();
+    for event in path.raw() {
+        match event {
+            PathEvent::Begin { at } => {
+                subpaths.push(vec![Point::new(at.x, at.y)]);
+            }
+            PathEvent::Line { to, .. } => {
+                if let Some(subpath) = subpaths.last_mut() {
+                    subpath.push(Point::new(to.x, to.y));
+                }
+            }
+            _ => {}
         }
     }
+
+    Path::new(|b| {
+        for mut points in subpaths {
+            // Sutherland-Hodgman, one side of the circle at a time
+            for (i, &a) in corners.iter().enumerate() {
+                let e = corners[(i + 1) % SIDES] - a;
+                let inside = |p: Point| e.x * (p.y - a.y) - e.y * (p.x - a.x) >= 0.;
+                let cross = |p: Point, q: Point| {
+                    let (sp, sq) = (
+                        e.x * (p.y - a.y) - e.y * (p.x - a.x),
+                        e.x * (q.y - a.y) - e.y * (q.x - a.x),
+                    );
+                    let t = sp / (sp - sq);
+                    Point::new(p.x + (q.x - p.x) * t, p.y + (q.y - p.y) * t)
+                };
+
+                let mut clipped = Vec::with_capacity(points.len());
+                for (j, &p) in points.iter().enumerate() {
+                    let q = points[(j + 1) % points.len()];
+                    match (inside(p), inside(q)) {
+                        (true, true) => clipped.push(q),
+                        (true, false) => clipped.push(cross(p, q)),
+                        (false, true) => {
+                            clipped.push(cross(p, q));
+                            clipped.push(q);
+                        }
+                        (false, false) => {}
+                    }
+                }
+                points = clipped;
+                if points.is_empty() {
+                    break;
+                }
+            }
+
+            if let Some((first, rest)) = points.split_first() {
+                b.move_to(*first);
+                for point in rest {
+                    b.line_to(*point);
+                }
+            }
+        }
+    })
+}
+
+// each component of the shape uses its own colors, if it has any
+fn draw_shape(
+    drawing: &mut Vec<IcedDrawCmd>,
+    shape: &Shape,
+    transform: &Transform2D<f32>,
+    pencolor: Color,
+    fillcolor: Color,
+    penwidth: f32,
+) {
+    if let Some(image) = shape.image() {
+        let corner = Transform2D::translation(-image.width() / 2., -image.height() / 2.);
+        let transform = corner.then(shape.image_transform()).then(transform);
+        drawing.push(IcedDrawCmd::Image(image.clone(), transform));
+    }
+
+    let penwidth = shape.outline().unwrap_or(penwidth);
+
+    for component in shape.components() {
+        let path = component.polygon.get_path().transform(transform);
+        let fill = component.fill.as_ref().map_or(fillcolor, Color::from);
+        let outline = component.outline.as_ref().map_or(pencolor, Color::from);
+        drawing.push(IcedDrawCmd::Fill(path.clone(), fill, FillRule::EvenOdd));
+        let style = StrokeStyle::default();
+        drawing.push(IcedDrawCmd::Stroke(path, outline, penwidth, style));
+    }
 }
 
 type IcedCommand<T> = iced::Command<T>;
@@ -244,9 +488,9 @@ pub(crate) struct IcedGuiFramework {
     tt: TurtleTask,
     gui: IcedGuiInternal,
     clear_cache: bool,
-    winsize: (f32, f32),   // width, height
-    mouse_pos: (f32, f32), // x, y
-    mouse_down: bool,
+    winsize: (f32, f32),             // width, height
+    mouse_pos: (f32, f32),           // x, y
+    mouse_down: Option<MouseButton>, // button being held, if any
 }
 
 #[derive(Default)]
@@ -256,7 +500,11 @@ struct IcedGuiInternal {
     popups: HashMap<WindowID, PopupData>,
     wcmds: Vec<IcedCommand<Message>>,
     bgcolor: TurtleColor,
-    resize_request: Option<(TurtleID, TurtleThread)>,
+    bgpic: Option<(TurtleImage, BgPicMode)>,
+    background: Option<image::Handle>, // the picture, fitted to the window
+    winsize: [isize; 2],
+    resize_request: Option<Sender<Response>>,
+    coords: Coordinates,
 }
 
 impl TurtleGui for IcedGuiInternal {
@@ -294,26 +542,9 @@ impl TurtleGui for IcedGuiInternal {
         turtle.has_new_cmd = true;
     }
 
-    fn clear_stamps(&mut self, turtle: TurtleID, count: StampCount) {
+    fn clear(&mut self, turtle: TurtleID) {
         let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
-        let all = turtle.cmds.len();
-        let (mut iter, mut count) = match count {
-            StampCount::Forward(count) => (Either::Right(turtle.cmds.iter_mut()), count),
-            StampCount::Reverse(count) => (Either::Left(turtle.cmds.iter_mut().rev()), count),
-            StampCount::All => (Either::Right(turtle.cmds.iter_mut()), all),
-        };
-
-        while count > 0 {
-            if let Some(cmd) = iter.next() {
-                if matches!(cmd, DrawCommand::DrawPolyAt(_, _, _)) {
-                    count -= 1;
-                    *cmd = DrawCommand::Filler
-                }
-            } else {
-                break;
-            }
-        }
-
+        turtle.cmds.clear();
         turtle.has_new_cmd = true;
     }
 
@@ -339,10 +570,6 @@ impl TurtleGui for IcedGuiInternal {
         turtle.cmds.push(DrawCommand::Filled(index));
     }
 
-    fn undo_count(&self, turtle: TurtleID) -> usize {
-        self.turtle.get(&turtle).expect("missing turtle").cmds.len()
-    }
-
     fn undo(&mut self, turtle: TurtleID) {
         let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
         turtle.has_new_cmd = true;
@@ -351,6 +578,7 @@ impl TurtleGui for IcedGuiInternal {
     fn pop(&mut self, turtle: TurtleID) -> Option<DrawCommand> {
         let turtle = self.turtle.get_mut(&turtle).expect("missing turtle");
         let cmd = turtle.cmds.pop();
+        turtle.has_new_cmd = true;
 
         if let Some(DrawCommand::Filled(index)) = &cmd {
             turtle.cmds[*index] = DrawCommand::Filler;
@@ -359,23 +587,36 @@ impl TurtleGui for IcedGuiInternal {
         cmd
     }
 
-    fn numinput(&mut self, turtle: TurtleID, thread: TurtleThread, title: &str, prompt: &str) {
-        self.generate_popup(PopupData::num_input(title, prompt, turtle, thread));
+    fn last_command(&self, turtle: TurtleID) -> Option<&DrawCommand> {
+        self.turtle
+            .get(&turtle)
+            .expect("missing turtle")
+            .cmds
+            .last()
+    }
+
+    fn numinput(&mut self, reply: Sender<Response>, title: &str, prompt: &str) {
+        self.generate_popup(PopupData::num_input(title, prompt, reply));
     }
 
-    fn textinput(&mut self, turtle: TurtleID, thread: TurtleThread, title: &str, prompt: &str) {
-        self.generate_popup(PopupData::text_input(title, prompt, turtle, thread));
+    fn textinput(&mut self, reply: Sender<Response>, title: &str, prompt: &str) {
+        self.generate_popup(PopupData::text_input(title, prompt, reply));
     }
 
     fn bgcolor(&mut self, color: TurtleColor) {
         self.bgcolor = color;
     }
 
-    fn resize(&mut self, turtle: TurtleID, thread: TurtleThread, width: isize, height: isize) {
+    fn bgpic(&mut self, bgpic: Option<(TurtleImage, BgPicMode)>) {
+        self.bgpic = bgpic;
+        self.fit_background(self.winsize);
+    }
+
+    fn resize(&mut self, reply: Sender<Response>, width: isize, height: isize) {
         let new_size = Size::new(width as f32, height as f32);
         self.wcmds
             .push(window::resize::<Message>(window::Id::MAIN, new_size));
-        self.resize_request = Some((turtle, thread));
+        self.resize_request = Some(reply);
     }
 
     fn set_visible(&mut self, turtle: TurtleID, visible: bool) {
@@ -388,6 +629,34 @@ impl TurtleGui for IcedGuiInternal {
         let turtle = self.turtle.get(&turtle).expect("missing turtle");
         !turtle.hide_turtle
     }
+
+    fn visible_shape(&self, turtle: TurtleID) -> Option<&TurtleShape> {
+        let turtle = self.turtle.get(&turtle).expect("missing turtle");
+        (!turtle.hide_turtle).then_some(&turtle.turtle_shape)
+    }
+
+    fn set_coordinates(&mut self, coords: Coordinates) {
+        self.coords = coords;
+        self.redraw_all();
+    }
+
+    fn scene(&self, size: [isize; 2]) -> Scene {
+        let view = self.coords.view([size[0] as f32, size[1] as f32]);
+        let turtles = self
+            .turtle_ids()
+            .iter()
+            .map(|id| {
+                let turtle = &self.turtle[id];
+                SceneTurtle::new(
+                    turtle.cmds.iter().map(|cmd| cmd.scaled(view)).collect(),
+                    turtle.turtle_shape.clone(),
+                    !turtle.hide_turtle,
+                )
+            })
+            .collect();
+
+        Scene::new(self.bgcolor, self.bgpic.clone(), size, turtles)
+    }
 }
 
 impl Application for IcedGuiFramework {
@@ -401,7 +670,7 @@ impl Application for IcedGuiFramework {
 
         let title = flags.title.clone();
         let mut tt = TurtleTask::new(&mut flags);
-        tt.run_turtle(func.unwrap());
+        let _ = tt.run_turtle(func.unwrap());
 
         let framework = Self {
             cache: Cache::default(),
@@ -410,7 +679,7 @@ impl Application for IcedGuiFramework {
             gui: IcedGuiInternal::new(WindowID::MAIN, PopupData::mainwin(&title)),
             winsize: (0., 0.),
             mouse_pos: (0., 0.),
-            mouse_down: false,
+            mouse_down: None,
         };
 
         (framework, IcedCommand::none())
@@ -432,8 +701,12 @@ impl Application for IcedGuiFramework {
                     self.clear_cache = false;
                 }
                 self.tt.tick(&mut self.gui);
-                if self.update_turtles() {
-                    self.clear_cache = true;
+                // until then, the window keeps showing the last frame
+                if self.tt.take_update() {
+                    if self.update_turtles() {
+                        self.clear_cache = true;
+                    }
+                    self.tt.frame_shown();
                 }
             }
             Message::AckError(id) => {
@@ -445,47 +718,41 @@ impl Application for IcedGuiFramework {
                 match &turtle_event {
                     TurtleEvent::WindowResize(x, y) => {
                         self.winsize = (*x as f32, *y as f32);
-                        if self.gui.resize_request.is_none() {
-                            self.tt.handle_event(None, None, turtle_event);
-                        } else {
-                            let (turtle, thread) =
-                                self.gui.resize_request.expect("missing resize data");
-                            self.tt
-                                .handle_event(Some(turtle), Some(thread), turtle_event);
+                        self.gui.fit_background([*x as isize, *y as isize]);
+                        self.gui.redraw_all();
+                        self.tt.handle_event(turtle_event, &self.gui);
+                        if let Some(reply) = self.gui.resize_request.take() {
+                            let _ = reply.send(Response::Done);
                         }
                     }
                     TurtleEvent::MousePosition(x, y) => {
                         self.mouse_pos = self.to_turtle_pos(x, y);
-                        if self.mouse_down {
+                        if let Some(button) = self.mouse_down {
                             self.tt.handle_event(
-                                None,
-                                None,
-                                TurtleEvent::MouseDrag(self.mouse_pos.0, self.mouse_pos.1),
+                                TurtleEvent::MouseDrag(self.mouse_pos.0, self.mouse_pos.1, button),
+                                &self.gui,
                             );
                         }
                     }
-                    TurtleEvent::MouseDrag(_, _) => unimplemented!(),
-                    TurtleEvent::MousePress(_x, _y) => {
-                        self.mouse_down = true;
+                    TurtleEvent::MouseDrag(..) => unimplemented!(),
+                    TurtleEvent::MousePress(_x, _y, button) => {
+                        self.mouse_down = Some(*button);
                         self.tt.handle_event(
-                            None,
-                            None,
-                            TurtleEvent::MousePress(self.mouse_pos.0, self.mouse_pos.1),
+                            TurtleEvent::MousePress(self.mouse_pos.0, self.mouse_pos.1, *button),
+                            &self.gui,
                         );
                     }
-                    TurtleEvent::MouseRelease(_x, _y) => {
-                        self.mouse_down = false;
+                    TurtleEvent::MouseRelease(_x, _y, button) => {
+                        self.mouse_down = None;
                         self.tt.handle_event(
-                            None,
-                            None,
-                            TurtleEvent::MouseRelease(self.mouse_pos.0, self.mouse_pos.1),
+                            TurtleEvent::MouseRelease(self.mouse_pos.0, self.mouse_pos.1, *button),
+                            &self.gui,
                         );
                     }
                     TurtleEvent::Unhandled => {}
-                    TurtleEvent::KeyPress(_) | TurtleEvent::KeyRelease(_) => {
-                        self.tt.handle_event(None, None, turtle_event);
+                    TurtleEvent::KeyPress(..) | TurtleEvent::KeyRelease(..) => {
+                        self.tt.handle_event(turtle_event, &self.gui);
                     }
-                    TurtleEvent::_Timer => todo!(),
                 }
             }
             Message::TextInputChanged(id, msg) => {
@@ -496,9 +763,7 @@ impl Application for IcedGuiFramework {
                 let mut popup = self.gui.popups.remove(&id).expect("looking up popup data");
                 match popup.get_response() {
                     Ok(response) => {
-                        let turtle = popup.turtle();
-                        let thread = popup.thread();
-                        self.tt.popup_result(turtle, thread, response);
+                        let _ = popup.reply().send(response);
                         self.gui.wcmds.push(window::close(id));
                     }
                     Err(message) => {
@@ -509,7 +774,7 @@ impl Application for IcedGuiFramework {
             }
             Message::Cancel(id) => {
                 let popup = self.gui.popups.remove(&id).expect("looking up popup data");
-                self.tt.popup_cancelled(popup.turtle(), popup.thread());
+                let _ = popup.reply().send(Response::Cancel);
                 self.gui.wcmds.push(window::close(id));
             }
         }
@@ -521,10 +786,13 @@ impl Application for IcedGuiFramework {
         win_id: iced::window::Id,
     ) -> iced::Element<'_, Self::Message, Self::Theme, iced::Renderer> {
         if win_id == WindowID::MAIN {
-            Canvas::new(self)
-                .width(Length::Fill)
-                .height(Length::Fill)
-                .into()
+            let canvas = Canvas::new(self).width(Length::Fill).height(Length::Fill);
+            Backdrop::new(
+                (&self.gui.bgcolor).into(),
+                self.gui.background.clone(),
+                canvas,
+            )
+            .into()
         } else {
             let popup = self
                 .gui
@@ -604,17 +872,9 @@ impl<Message> canvas::Program<Message> for IcedGuiFramework {
     ) -> Vec<<Renderer as canvas::Renderer>::Geometry> {
         let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
             let center = frame.center();
-            frame.fill_rectangle(
-                [0., 0.].into(),
-                bounds.size(),
-                Fill {
-                    style: stroke::Style::Solid((&self.gui.bgcolor).into()),
-                    rule: Rule::NonZero,
-                },
-            );
             frame.translate([center.x, center.y].into());
-            for turtle in self.gui.turtle.values() {
-                turtle.draw(frame);
+            for tid in self.gui.turtle_ids() {
+                self.gui.turtle[&tid].draw(frame);
             }
         });
         vec![geometry]
@@ -640,12 +900,13 @@ impl IcedGuiFramework {
     // returns true if the cache should be cleared
     fn update_turtles(&mut self) -> bool {
         let mut done = true;
+        let view = self.gui.coords.view([self.winsize.0, self.winsize.1]);
 
         for (tid, turtle) in self.gui.turtle.iter_mut() {
             let (pct, prog) = self.tt.progress(*tid);
             if turtle.has_new_cmd {
                 done = false;
-                turtle.convert(pct);
+                turtle.convert(pct, view);
                 if prog.is_done(pct) {
                     turtle.has_new_cmd = false;
                 }
@@ -656,9 +917,13 @@ impl IcedGuiFramework {
     }
 
     fn to_turtle_pos(&self, x: &f32, y: &f32) -> (f32, f32) {
-        let x = *x;
-        let y = *y;
-        (x - self.winsize.0 / 2., -(y - self.winsize.1 / 2.))
+        let [vx, vy] = self.gui.coords.view([self.winsize.0, self.winsize.1]);
+        let canvas = ScreenPosition::new(
+            (x - self.winsize.0 / 2.) / vx,
+            (y - self.winsize.1 / 2.) / vy,
+        );
+        let [x, y] = self.gui.coords.user_pos(canvas);
+        (x, y)
     }
 }
 
@@ -673,6 +938,33 @@ impl IcedGuiInternal {
         this
     }
 
+    fn fit_background(&mut self, size: [isize; 2]) {
+        self.winsize = size;
+        self.background = self
+            .bgpic
+            .as_ref()
+            .and_then(|(image, mode)| image.background(*mode, size))
+            .map(|background| {
+                let [width, height] = [background.width(), background.height()];
+                let pixels = picture::rgba(background.pixmap());
+                image::Handle::from_pixels(width as u32, height as u32, pixels)
+            });
+    }
+
+    // convert every turtle's commands again, eg. after the view has changed
+    fn redraw_all(&mut self) {
+        for turtle in self.turtle.values_mut() {
+            turtle.has_new_cmd = true;
+        }
+    }
+
+    // turtles are drawn in the order they were created
+    fn turtle_ids(&self) -> Vec<TurtleID> {
+        let mut ids: Vec<TurtleID> = self.turtle.keys().copied().collect();
+        ids.sort_by_key(|id| id.index());
+        ids
+    }
+
     fn generate_popup(&mut self, popupdata: PopupData) {
         let (id, wcmd) = window::spawn(window::Settings {
             size: [250f32, 150f32].into(),
@@ -692,28 +984,24 @@ impl From<Event> for TurtleEvent {
                 mouse::Event::CursorMoved { position } => {
                     TurtleEvent::MousePosition(position.x, position.y)
                 }
-                mouse::Event::ButtonPressed(_) => TurtleEvent::MousePress(0., 0.),
-                mouse::Event::ButtonReleased(_) => TurtleEvent::MouseRelease(0., 0.),
+                mouse::Event::ButtonPressed(button) => match button.try_into() {
+                    Ok(button) => TurtleEvent::MousePress(0., 0., button),
+                    Err(_) => TurtleEvent::Unhandled,
+                },
+                mouse::Event::ButtonReleased(button) => match button.try_into() {
+                    Ok(button) => TurtleEvent::MouseRelease(0., 0., button),
+                    Err(_) => TurtleEvent::Unhandled,
+                },
                 _ => TurtleEvent::Unhandled,
             }
         }
 
         match event {
-            Event::Keyboard(KeyReleased { key, .. }) => {
-                if let Key::Character(s) = key.as_ref() {
-                    let ch = s.chars().next().unwrap();
-                    TurtleEvent::KeyRelease(ch)
-                } else {
-                    TurtleEvent::Unhandled
-                }
+            Event::Keyboard(KeyReleased { key, modifiers, .. }) => {
+                TurtleEvent::KeyRelease(convert_key(key), modifiers.into())
             }
-            Event::Keyboard(KeyPressed { key, .. }) => {
-                if let Key::Character(s) = key.as_ref() {
-                    let ch = s.chars().next().unwrap();
-                    TurtleEvent::KeyPress(ch)
-                } else {
-                    TurtleEvent::Unhandled
-                }
+            Event::Keyboard(KeyPressed { key, modifiers, .. }) => {
+                TurtleEvent::KeyPress(convert_key(key), modifiers.into())
             }
             Event::Window(window::Id::MAIN, Resized { width, height }) => {
                 TurtleEvent::WindowResize(width, height)
@@ -724,3 +1012,71 @@ impl From<Event> for TurtleEvent {
         }
     }
 }
+
+fn convert_key(key: Key) -> TurtleKey {
+    match key {
+        Key::Character(s) => s
+            .chars()
+            .next()
+            .map_or(TurtleKey::Other(s.into()), Into::into),
+        Key::Named(named) => match named {
+            Named::ArrowUp => TurtleKey::Up,
+            Named::ArrowDown => TurtleKey::Down,
+            Named::ArrowLeft => TurtleKey::Left,
+            Named::ArrowRight => TurtleKey::Right,
+            Named::Space => TurtleKey::Space,
+            Named::Enter => TurtleKey::Return,
+            Named::Escape => TurtleKey::Escape,
+            Named::Tab => TurtleKey::Tab,
+            Named::Backspace => TurtleKey::BackSpace,
+            Named::Delete => TurtleKey::Delete,
+            Named::Insert => TurtleKey::Insert,
+            Named::Home => TurtleKey::Home,
+            Named::End => TurtleKey::End,
+            Named::PageUp => TurtleKey::PageUp,
+            Named::PageDown => TurtleKey::PageDown,
+            Named::F1 => TurtleKey::F(1),
+            Named::F2 => TurtleKey::F(2),
+            Named::F3 => TurtleKey::F(3),
+            Named::F4 => TurtleKey::F(4),
+            Named::F5 => TurtleKey::F(5),
+            Named::F6 => TurtleKey::F(6),
+            Named::F7 => TurtleKey::F(7),
+            Named::F8 => TurtleKey::F(8),
+            Named::F9 => TurtleKey::F(9),
+            Named::F10 => TurtleKey::F(10),
+            Named::F11 => TurtleKey::F(11),
+            Named::F12 => TurtleKey::F(12),
+            Named::Shift => TurtleKey::Shift,
+            Named::Control => TurtleKey::Control,
+            Named::Alt => TurtleKey::Alt,
+            Named::Super => TurtleKey::Super,
+            other => TurtleKey::Other(format!("{other:?}")),
+        },
+        Key::Unidentified => TurtleKey::Other("Unidentified".into()),
+    }
+}
+
+impl From<Modifiers> for KeyModifiers {
+    fn from(modifiers: Modifiers) -> Self {
+        Self {
+            shift: modifiers.shift(),
+            control: modifiers.control(),
+            alt: modifiers.alt(),
+            logo: modifiers.logo(),
+        }
+    }
+}
+
+impl TryFrom<mouse::Button> for MouseButton {
+    type Error = mouse::Button;
+
+    fn try_from(button: mouse::Button) -> Result<Self, Self::Error> {
+        match button {
+            mouse::Button::Left => Ok(MouseButton::Left),
+            mouse::Button::Middle => Ok(MouseButton::Middle),
+            mouse::Button::Right => Ok(MouseButton::Right),
+            other => Err(other),
+        }
+    }
+}
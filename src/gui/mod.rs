@@ -1,12 +1,22 @@
+pub(crate) mod backdrop;
 pub(crate) mod events;
+pub(crate) mod headless;
 pub(crate) mod popup;
 
 use crate::color_names::TurtleColor;
-use crate::{generate::DrawCommand, polygon::TurtleShape, ScreenPosition};
+use crate::{
+    coords::Coordinates,
+    generate::DrawCommand,
+    picture::{BgPicMode, TurtleImage},
+    polygon::TurtleShape,
+    scene::Scene,
+    ScreenPosition,
+};
 
 pub(crate) mod iced_gui;
 
-use crate::turtle::types::{TurtleID, TurtleThread};
+use crate::{comms::Response, turtle::types::TurtleID};
+use std::sync::mpsc::Sender;
 
 pub(crate) trait TurtleGui: Default + Sized {
     // Generate a new connection to the windowing system
@@ -21,8 +31,8 @@ pub(crate) trait TurtleGui: Default + Sized {
     // clear a given stamp id
     fn clear_stamp(&mut self, turtle: TurtleID, stamp: usize);
 
-    // clear the first/last quantity of stamps
-    fn clear_stamps(&mut self, turtle: TurtleID, count: StampCount);
+    // erase everything the turtle has drawn, stamps included
+    fn clear(&mut self, turtle: TurtleID);
 
     // get the name of the current turtle's shape
     fn get_turtle_shape_name(&mut self, turtle_id: TurtleID) -> String;
@@ -40,29 +50,41 @@ pub(crate) trait TurtleGui: Default + Sized {
     // start the 'undo' drawing process
     fn undo(&mut self, turtle: TurtleID);
 
-    // remove last command and start to undo the next
+    // remove the last command
     fn pop(&mut self, turtle: TurtleID) -> Option<DrawCommand>;
 
-    // how many commands can be undone
-    fn undo_count(&self, turtle: TurtleID) -> usize;
+    // the command that the next 'undo' will remove
+    fn last_command(&self, turtle: TurtleID) -> Option<&DrawCommand>;
 
-    // read a numeric value from the user
-    fn numinput(&mut self, turtle: TurtleID, thread: TurtleThread, title: &str, prompt: &str);
+    // read a numeric value from the user, and send it to `reply`
+    fn numinput(&mut self, reply: Sender<Response>, title: &str, prompt: &str);
 
-    // read a text string from the user
-    fn textinput(&mut self, turtle: TurtleID, thread: TurtleThread, title: &str, prompt: &str);
+    // read a text string from the user, and send it to `reply`
+    fn textinput(&mut self, reply: Sender<Response>, title: &str, prompt: &str);
 
     // set the background color
     fn bgcolor(&mut self, color: TurtleColor);
 
-    // resize the window
-    fn resize(&mut self, turtle: TurtleID, thread: TurtleThread, width: isize, height: isize);
+    // show a picture behind the drawing, or remove it
+    fn bgpic(&mut self, bgpic: Option<(TurtleImage, BgPicMode)>);
+
+    // resize the window, and tell `reply` once it's done
+    fn resize(&mut self, reply: Sender<Response>, width: isize, height: isize);
 
     // show or hide the turtle
     fn set_visible(&mut self, turtle: TurtleID, visible: bool);
 
     // get the current visibility status
     fn is_visible(&self, turtle: TurtleID) -> bool;
+
+    // the turtle's shape, if it's being shown
+    fn visible_shape(&self, turtle: TurtleID) -> Option<&TurtleShape>;
+
+    // how user coordinates map onto the window
+    fn set_coordinates(&mut self, coords: Coordinates);
+
+    // take a snapshot of everything that's been drawn
+    fn scene(&self, size: [isize; 2]) -> Scene;
 }
 
 #[derive(Default, Debug, Clone, Copy)]
@@ -81,9 +103,3 @@ impl Progression {
         }
     }
 }
-
-pub(crate) enum StampCount {
-    Forward(usize), // delete from the beginning of the stamp list
-    Reverse(usize), // delete from the end of the stamp list
-    All,
-}
@@ -1,17 +1,16 @@
+use std::sync::mpsc::Sender;
+
 use crate::comms::Response;
-use crate::turtle::types::{TurtleID, TurtleThread};
 
 #[derive(Default)]
 enum PopupType {
     TextInput {
-        thread: TurtleThread,
-        turtle: TurtleID,
+        reply: Sender<Response>,
         prompt: String,
         text_input_field: String,
     },
     NumericalInput {
-        thread: TurtleThread,
-        turtle: TurtleID,
+        reply: Sender<Response>,
         prompt: String,
         text_input_field: String,
     },
@@ -21,21 +20,10 @@ enum PopupType {
 }
 
 impl PopupType {
-    fn thread(&self) -> TurtleThread {
+    fn reply(&self) -> &Sender<Response> {
         match self {
-            PopupType::TextInput { thread, .. } | PopupType::NumericalInput { thread, .. } => {
-                *thread
-            }
-            _ => panic!("invalid popup for turtle id"),
-        }
-    }
-
-    fn turtle(&self) -> TurtleID {
-        match self {
-            PopupType::TextInput { turtle, .. } | PopupType::NumericalInput { turtle, .. } => {
-                *turtle
-            }
-            _ => panic!("invalid popup for turtle id"),
+            PopupType::TextInput { reply, .. } | PopupType::NumericalInput { reply, .. } => reply,
+            _ => panic!("invalid popup for reply"),
         }
     }
 
@@ -76,27 +64,25 @@ impl PopupData {
         }
     }
 
-    pub fn text_input(title: &str, prompt: &str, turtle: TurtleID, thread: TurtleThread) -> Self {
+    pub fn text_input(title: &str, prompt: &str, reply: Sender<Response>) -> Self {
         Self {
             title: title.to_string(),
             err: None,
             popup: PopupType::TextInput {
                 prompt: prompt.to_string(),
-                turtle,
-                thread,
+                reply,
                 text_input_field: "".to_string(),
             },
         }
     }
 
-    pub fn num_input(title: &str, prompt: &str, turtle: TurtleID, thread: TurtleThread) -> Self {
+    pub fn num_input(title: &str, prompt: &str, reply: Sender<Response>) -> Self {
         Self {
             title: title.to_string(),
             err: None,
             popup: PopupType::NumericalInput {
                 prompt: prompt.to_string(),
-                thread,
-                turtle,
+                reply,
                 text_input_field: "".to_string(),
             },
         }
@@ -118,12 +104,8 @@ impl PopupData {
         }
     }
 
-    pub fn thread(&self) -> TurtleThread {
-        self.popup.thread()
-    }
-
-    pub fn turtle(&self) -> TurtleID {
-        self.popup.turtle()
+    pub fn reply(&self) -> &Sender<Response> {
+        self.popup.reply()
     }
 
     pub(crate) fn prompt(&self) -> &str {
@@ -1,27 +1,522 @@
-use crate::{command::InputCmd, turtle::Turtle};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+    time::Duration,
+};
 
+use crate::{
+    command::{InputCmd, ScreenCmd},
+    screen::Screen,
+    turtle::Turtle,
+};
+
+/// Which mouse button was pressed. Converts from python's button numbers
+/// (1 = left, 2 = middle, 3 = right).
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+impl From<u8> for MouseButton {
+    fn from(button: u8) -> Self {
+        match button {
+            2 => Self::Middle,
+            3 => Self::Right,
+            _ => Self::Left,
+        }
+    }
+}
+
+/// A key on the keyboard. Converts from a `char`, or from a python (tk) key
+/// name such as "Up", "space", "Return", "Escape" or "F1".
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub enum TurtleKey {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Space,
+    Return,
+    Escape,
+    Tab,
+    BackSpace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8), // function keys, F(1) to F(12)
+    Shift,
+    Control,
+    Alt,
+    Super,
+    Other(String), // any other key, by name
+}
+
+impl From<char> for TurtleKey {
+    fn from(ch: char) -> Self {
+        match ch {
+            ' ' => Self::Space,
+            '\n' | '\r' => Self::Return,
+            '\t' => Self::Tab,
+            '\u{1b}' => Self::Escape,
+            '\u{8}' => Self::BackSpace,
+            '\u{7f}' => Self::Delete,
+            ch => Self::Char(ch),
+        }
+    }
+}
+
+impl From<&str> for TurtleKey {
+    fn from(name: &str) -> Self {
+        let mut chars = name.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            return ch.into();
+        }
+
+        match name {
+            "Up" => Self::Up,
+            "Down" => Self::Down,
+            "Left" => Self::Left,
+            "Right" => Self::Right,
+            "space" => Self::Space,
+            "Return" | "Enter" => Self::Return,
+            "Escape" => Self::Escape,
+            "Tab" => Self::Tab,
+            "BackSpace" => Self::BackSpace,
+            "Delete" => Self::Delete,
+            "Insert" => Self::Insert,
+            "Home" => Self::Home,
+            "End" => Self::End,
+            "Prior" | "PageUp" => Self::PageUp,
+            "Next" | "PageDown" => Self::PageDown,
+            "Shift_L" | "Shift_R" | "Shift" => Self::Shift,
+            "Control_L" | "Control_R" | "Control" => Self::Control,
+            "Alt_L" | "Alt_R" | "Alt" => Self::Alt,
+            "Super_L" | "Super_R" | "Super" => Self::Super,
+            _ => match name.strip_prefix('F').and_then(|num| num.parse().ok()) {
+                Some(num @ 1..=12) => Self::F(num),
+                _ => Self::Other(name.into()),
+            },
+        }
+    }
+}
+
+/// Which modifier keys were held down when a key was pressed or released
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct KeyModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub logo: bool, // the Super/Windows/Command key
+}
+
+/// Called with the key that was pressed or released, and the modifiers held
+pub type KeyHandler = Box<dyn FnMut(&mut Turtle, TurtleKey, KeyModifiers) + Send>;
+
+/// Called with the mouse position (in turtle coordinates) and the button
+pub type MouseHandler = Box<dyn FnMut(&mut Turtle, f32, f32, MouseButton) + Send>;
+
+pub(crate) type TimerHandler = Box<dyn FnMut(&mut Turtle) + Send>;
+
+/// Like `KeyHandler`, for keys bound on the `Screen`
+pub type ScreenKeyHandler = Box<dyn FnMut(&Screen, TurtleKey, KeyModifiers) + Send>;
+
+/// Like `MouseHandler`, for clicks bound on the `Screen`
+pub type ScreenMouseHandler = Box<dyn FnMut(&Screen, f32, f32, MouseButton) + Send>;
+
+/// Anything that can be bound to an event: a function, a closure, a boxed
+/// handler, or `None` to unbind whatever was bound before (as in python).
+///
+/// Rust can't work out the argument types of a closure passed this way, so
+/// write them out: `turtle.onkey(|t: &mut Turtle, _key, _mods| t.forward(10.), "Up")`.
+pub trait IntoHandler<H> {
+    fn into_handler(self) -> Option<H>;
+}
+
+impl<F: FnMut(&mut Turtle, TurtleKey, KeyModifiers) + Send + 'static> IntoHandler<KeyHandler>
+    for F
+{
+    fn into_handler(self) -> Option<KeyHandler> {
+        Some(Box::new(self))
+    }
+}
+
+impl IntoHandler<KeyHandler> for Option<KeyHandler> {
+    fn into_handler(self) -> Option<KeyHandler> {
+        self
+    }
+}
+
+impl<F: FnMut(&mut Turtle, f32, f32, MouseButton) + Send + 'static> IntoHandler<MouseHandler>
+    for F
+{
+    fn into_handler(self) -> Option<MouseHandler> {
+        Some(Box::new(self))
+    }
+}
+
+impl IntoHandler<MouseHandler> for Option<MouseHandler> {
+    fn into_handler(self) -> Option<MouseHandler> {
+        self
+    }
+}
+
+impl<F: FnMut(&Screen, TurtleKey, KeyModifiers) + Send + 'static> IntoHandler<ScreenKeyHandler>
+    for F
+{
+    fn into_handler(self) -> Option<ScreenKeyHandler> {
+        Some(Box::new(self))
+    }
+}
+
+impl IntoHandler<ScreenKeyHandler> for Option<ScreenKeyHandler> {
+    fn into_handler(self) -> Option<ScreenKeyHandler> {
+        self
+    }
+}
+
+impl<F: FnMut(&Screen, f32, f32, MouseButton) + Send + 'static> IntoHandler<ScreenMouseHandler>
+    for F
+{
+    fn into_handler(self) -> Option<ScreenMouseHandler> {
+        Some(Box::new(self))
+    }
+}
+
+impl IntoHandler<ScreenMouseHandler> for Option<ScreenMouseHandler> {
+    fn into_handler(self) -> Option<ScreenMouseHandler> {
+        self
+    }
+}
+
+// A handler shared between the turtle task and the threads it gets run on.
+// Only one copy of the handler runs at a time.
+pub(crate) struct Callback<H>(Arc<Mutex<H>>);
+
+impl<H> Callback<H> {
+    pub(crate) fn new(func: H) -> Self {
+        Self(Arc::new(Mutex::new(func)))
+    }
+
+    // a handler that panicked once can still be called again
+    pub(crate) fn lock(&self) -> MutexGuard<'_, H> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<H> Clone for Callback<H> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<H> fmt::Debug for Callback<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Callback(..)")
+    }
+}
+
+/// Returned when binding an event handler; use it to unbind the handler again.
+/// Can be cloned and sent to other threads.
+#[derive(Debug, Clone, Default)]
+pub struct HandlerHandle {
+    removed: Arc<AtomicBool>,
+}
+
+impl HandlerHandle {
+    pub fn remove(&self) {
+        self.removed.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_removed(&self) -> bool {
+        self.removed.load(Ordering::Relaxed)
+    }
+}
+
+/// Returned by `ontimer()` and `oninterval()`; use it to stop the timer before
+/// it fires again. Can be cloned and sent to other threads.
+#[derive(Debug, Clone, Default)]
+pub struct TimerHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl TimerHandle {
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+// Keys and clicks on the screen have one binding each, whichever turtle or
+// screen made it: binding them again replaces what was there before.
 impl Turtle {
-    pub fn onkey(&self, func: fn(&mut Turtle, char), key: char) {
-        self.onkeyrelease(func, key);
+    /// Call `func` with this turtle when `key` is released
+    pub fn onkey<H, K>(&self, func: H, key: K) -> HandlerHandle
+    where
+        H: IntoHandler<KeyHandler>,
+        K: Into<TurtleKey>,
+    {
+        self.onkeyrelease(func, key)
+    }
+
+    pub fn onkeyrelease<H, K>(&self, func: H, key: K) -> HandlerHandle
+    where
+        H: IntoHandler<KeyHandler>,
+        K: Into<TurtleKey>,
+    {
+        self.bind(func, |func, handle| {
+            InputCmd::KeyRelease(func, key.into(), handle)
+        })
+    }
+
+    /// Call `func` with this turtle when `key` is pressed
+    pub fn onkeypress<H, K>(&self, func: H, key: K) -> HandlerHandle
+    where
+        H: IntoHandler<KeyHandler>,
+        K: Into<TurtleKey>,
+    {
+        self.bind(func, |func, handle| {
+            InputCmd::KeyPress(func, key.into(), handle)
+        })
+    }
+
+    /// Call `func` when `button` is pressed over this turtle
+    pub fn onclick<H, B>(&self, func: H, button: B) -> HandlerHandle
+    where
+        H: IntoHandler<MouseHandler>,
+        B: Into<MouseButton>,
+    {
+        let button = button.into();
+        self.bind(func, |func, handle| {
+            InputCmd::MousePress(func, button, handle)
+        })
+    }
+
+    /// Call `func` when `button` is released after clicking on this turtle
+    pub fn onrelease<H, B>(&self, func: H, button: B) -> HandlerHandle
+    where
+        H: IntoHandler<MouseHandler>,
+        B: Into<MouseButton>,
+    {
+        let button = button.into();
+        self.bind(func, |func, handle| {
+            InputCmd::MouseRelease(func, button, handle)
+        })
+    }
+
+    /// Call `func` as the mouse moves after clicking on this turtle with `button`
+    pub fn ondrag<H, B>(&self, func: H, button: B) -> HandlerHandle
+    where
+        H: IntoHandler<MouseHandler>,
+        B: Into<MouseButton>,
+    {
+        let button = button.into();
+        self.bind(func, |func, handle| {
+            InputCmd::MouseDrag(func, button, handle)
+        })
     }
 
-    pub fn onkeyrelease(&self, func: fn(&mut Turtle, char), key: char) {
-        self.do_input(InputCmd::KeyRelease(func, key));
+    /// Call `func` with this turtle when `button` is pressed anywhere on the
+    /// canvas
+    pub fn onscreenclick<H, B>(&self, func: H, button: B) -> HandlerHandle
+    where
+        H: IntoHandler<MouseHandler>,
+        B: Into<MouseButton>,
+    {
+        let button = button.into();
+        self.bind(func, |func, handle| {
+            InputCmd::ScreenClick(func, button, handle)
+        })
     }
 
-    pub fn onkeypress(&self, func: fn(&mut Turtle, char), key: char) {
-        self.do_input(InputCmd::KeyPress(func, key));
+    /// Call `func` once, after `millis` milliseconds
+    pub fn ontimer<F>(&self, func: F, millis: u64) -> TimerHandle
+    where
+        F: FnMut(&mut Turtle) + Send + 'static,
+    {
+        self.timer(Box::new(func), millis, false)
     }
 
-    pub fn onclick(&self, func: fn(&mut Turtle, f32, f32)) {
-        self.do_input(InputCmd::MousePress(func));
+    /// Call `func` every `millis` milliseconds, until the handle is cancelled
+    pub fn oninterval<F>(&self, func: F, millis: u64) -> TimerHandle
+    where
+        F: FnMut(&mut Turtle) + Send + 'static,
+    {
+        self.timer(Box::new(func), millis, true)
     }
 
-    pub fn onrelease(&self, func: fn(&mut Turtle, f32, f32)) {
-        self.do_input(InputCmd::MouseRelease(func));
+    fn timer(&self, func: TimerHandler, millis: u64, repeat: bool) -> TimerHandle {
+        let handle = TimerHandle::default();
+        self.do_input(InputCmd::Timer(
+            Callback::new(func),
+            Duration::from_millis(millis),
+            repeat,
+            handle.clone(),
+        ));
+        handle
     }
 
-    pub fn ondrag(&self, func: fn(&mut Turtle, f32, f32)) {
-        self.do_input(InputCmd::MouseDrag(func));
+    fn bind<H, F>(
+        &self,
+        func: F,
+        cmd: impl FnOnce(Option<Callback<H>>, HandlerHandle) -> InputCmd,
+    ) -> HandlerHandle
+    where
+        F: IntoHandler<H>,
+    {
+        let handle = HandlerHandle::default();
+        let func = func.into_handler().map(Callback::new);
+        self.do_input(cmd(func, handle.clone()));
+        handle
+    }
+}
+
+impl Screen {
+    pub fn onkey<H, K>(&self, func: H, key: K) -> HandlerHandle
+    where
+        H: IntoHandler<ScreenKeyHandler>,
+        K: Into<TurtleKey>,
+    {
+        self.onkeyrelease(func, key)
+    }
+
+    pub fn onkeyrelease<H, K>(&self, func: H, key: K) -> HandlerHandle
+    where
+        H: IntoHandler<ScreenKeyHandler>,
+        K: Into<TurtleKey>,
+    {
+        self.bind(func, |func, handle| {
+            ScreenCmd::KeyRelease(func, key.into(), handle)
+        })
+    }
+
+    pub fn onkeypress<H, K>(&self, func: H, key: K) -> HandlerHandle
+    where
+        H: IntoHandler<ScreenKeyHandler>,
+        K: Into<TurtleKey>,
+    {
+        self.bind(func, |func, handle| {
+            ScreenCmd::KeyPress(func, key.into(), handle)
+        })
+    }
+
+    /// Call `func` when `button` is pressed anywhere on the canvas
+    pub fn onscreenclick<H, B>(&self, func: H, button: B) -> HandlerHandle
+    where
+        H: IntoHandler<ScreenMouseHandler>,
+        B: Into<MouseButton>,
+    {
+        let button = button.into();
+        self.bind(func, |func, handle| {
+            ScreenCmd::ScreenClick(func, button, handle)
+        })
+    }
+
+    fn bind<H, F>(
+        &self,
+        func: F,
+        cmd: impl FnOnce(Option<Callback<H>>, HandlerHandle) -> ScreenCmd,
+    ) -> HandlerHandle
+    where
+        F: IntoHandler<H>,
+    {
+        let handle = HandlerHandle::default();
+        let func = func.into_handler().map(Callback::new);
+        self.do_command(cmd(func, handle.clone()));
+        handle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+    use crate::testing::{line_ends, run};
+
+    const WAIT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn python_key_names() {
+        assert_eq!(TurtleKey::from("Up"), TurtleKey::Up);
+        assert_eq!(TurtleKey::from("space"), TurtleKey::Space);
+        assert_eq!(TurtleKey::from("Return"), TurtleKey::Return);
+        assert_eq!(TurtleKey::from("Prior"), TurtleKey::PageUp);
+        assert_eq!(TurtleKey::from("Next"), TurtleKey::PageDown);
+        assert_eq!(TurtleKey::from("Shift_R"), TurtleKey::Shift);
+        assert_eq!(TurtleKey::from("Control_L"), TurtleKey::Control);
+        assert_eq!(TurtleKey::from("F1"), TurtleKey::F(1));
+        assert_eq!(TurtleKey::from("F12"), TurtleKey::F(12));
+    }
+
+    #[test]
+    fn single_characters_are_keys() {
+        assert_eq!(TurtleKey::from("a"), TurtleKey::Char('a'));
+        assert_eq!(TurtleKey::from("F"), TurtleKey::Char('F'));
+        assert_eq!(TurtleKey::from(" "), TurtleKey::Space);
+        assert_eq!(TurtleKey::from('\n'), TurtleKey::Return);
+        assert_eq!(TurtleKey::from('\u{1b}'), TurtleKey::Escape);
+    }
+
+    #[test]
+    fn unknown_names_are_kept() {
+        assert_eq!(TurtleKey::from("F13"), TurtleKey::Other("F13".into()));
+        assert_eq!(TurtleKey::from("Fx"), TurtleKey::Other("Fx".into()));
+        assert_eq!(TurtleKey::from("Menu"), TurtleKey::Other("Menu".into()));
+    }
+
+    #[test]
+    fn timers_run_once() {
+        let scene = run(|turtle| {
+            let (done, finished) = mpsc::channel();
+            turtle.ontimer(
+                move |turtle| {
+                    turtle.forward(20);
+                    let _ = done.send(());
+                },
+                10,
+            );
+            finished.recv_timeout(WAIT).unwrap();
+
+            let cancelled = turtle.ontimer(|turtle| turtle.forward(100), 50);
+            cancelled.cancel();
+            std::thread::sleep(Duration::from_millis(100));
+        });
+
+        assert_eq!(line_ends(&scene.turtles[0].commands), [[20., 0.]]);
+    }
+
+    #[test]
+    fn intervals_repeat_until_cancelled() {
+        run(|turtle| {
+            let (tick, ticks) = mpsc::channel();
+            let interval = turtle.oninterval(
+                move |_| {
+                    let _ = tick.send(());
+                },
+                5,
+            );
+            for _ in 0..3 {
+                ticks.recv_timeout(WAIT).unwrap();
+            }
+            assert!(!interval.is_cancelled());
+
+            interval.cancel();
+            std::thread::sleep(Duration::from_millis(50));
+            while ticks.try_recv().is_ok() {}
+            std::thread::sleep(Duration::from_millis(50));
+            assert!(ticks.try_recv().is_err());
+        });
     }
 }
@@ -1,16 +1,39 @@
+pub use coords::TurtleMode;
+pub use draw::TurtlePoint;
+pub use fill::{FillStyle, Gradient, Hatch, HatchKind};
+pub use generate::{CirclePos, DrawCommand, LineInfo};
+pub use input::{
+    HandlerHandle, IntoHandler, KeyHandler, KeyModifiers, MouseButton, MouseHandler,
+    ScreenKeyHandler, ScreenMouseHandler, TimerHandle, TurtleKey,
+};
 use lyon_tessellation::geom::euclid::{Point2D, UnknownUnit};
-pub use polygon::TurtleShapeName;
+pub use pen::PenState;
+pub use picture::BgPicMode;
+pub use polygon::{FillRule, Shape, TurtlePolygon, TurtleShapeName};
+pub use scene::{Scene, SceneTurtle};
+pub use screen::Screen;
+pub use stroke::{LineCap, LineJoin};
 pub use turtle::{Turtle, TurtleArgs};
 
 pub mod color_names;
 mod command;
 mod comms;
+mod coords;
 mod draw;
+mod fill;
 mod generate;
 mod gui;
 mod input;
+mod pen;
+mod picture;
 mod polygon;
+mod scene;
+mod screen;
 pub mod speed;
+mod stroke;
+#[cfg(test)]
+mod testing;
+pub mod text;
 mod turtle;
 
 pub const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
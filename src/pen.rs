@@ -0,0 +1,34 @@
+use crate::{color_names::TurtleColor, speed::TurtleSpeed};
+
+/// Everything about how a turtle draws, as in the dictionary returned by
+/// python's `pen()`. Hand it back to `set_pen()` to restore it all at once.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PenState {
+    pub pendown: bool,
+    pub pencolor: TurtleColor,
+    pub fillcolor: TurtleColor,
+    pub pensize: f32,
+    pub speed: TurtleSpeed,
+    pub shown: bool,
+    pub stretchfactor: (f32, f32), // stretch_wid, stretch_len
+    pub outline: Option<f32>,      // `None` outlines the shape with the pen size
+    pub shearfactor: f32,
+    pub tilt: f32,
+}
+
+impl Default for PenState {
+    fn default() -> Self {
+        Self {
+            pendown: true,
+            pencolor: "black".into(),
+            fillcolor: "black".into(),
+            pensize: 1.,
+            speed: TurtleSpeed::default(),
+            shown: true,
+            stretchfactor: (1., 1.),
+            outline: None,
+            shearfactor: 0.,
+            tilt: 0.,
+        }
+    }
+}
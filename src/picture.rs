@@ -0,0 +1,337 @@
+use std::{
+    collections::HashMap,
+    fmt, io,
+    path::Path,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use iced::{
+    widget::canvas::{self, path::Builder},
+    Color, Point, Size,
+};
+use image::ImageError;
+use lyon_tessellation::geom::euclid::{default::Transform2D, Rect};
+use tiny_skia::{FilterQuality, IntSize, Pixmap, PixmapPaint, Transform};
+
+// how many turned or stretched copies of a picture to keep
+const TRANSFORMED_LIMIT: usize = 64;
+
+// a picture, and where its top left corner goes on the canvas
+type Placed = (TurtleImage, [f32; 2]);
+
+/// How `bgpic()` fits the picture into the window
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum BgPicMode {
+    #[default]
+    Centered,
+    Tiled,
+    Stretched,
+}
+
+impl From<&str> for BgPicMode {
+    fn from(mode: &str) -> Self {
+        match mode {
+            "tile" | "tiled" => Self::Tiled,
+            "stretch" | "stretched" => Self::Stretched,
+            _ => Self::Centered,
+        }
+    }
+}
+
+// A picture loaded from a PNG or GIF file, shared by every shape, stamp and
+// background that uses it
+#[derive(Clone)]
+pub(crate) struct TurtleImage {
+    data: Arc<ImageData>,
+}
+
+struct ImageData {
+    pixmap: Pixmap,
+    png: OnceLock<Vec<u8>>,
+    // The iced canvas can't draw images, so they're drawn as one path for
+    // each color, made of the runs of pixels in that color
+    fills: OnceLock<Vec<(canvas::Path, Color)>>,
+    // the picture as shapes and stamps draw it, turned and stretched but not
+    // yet moved into place, by the linear part of the transform
+    transformed: Mutex<HashMap<[u32; 4], Option<Placed>>>,
+}
+
+impl fmt::Debug for TurtleImage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TurtleImage")
+            .field("width", &self.width())
+            .field("height", &self.height())
+            .finish()
+    }
+}
+
+impl TurtleImage {
+    pub(crate) fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let image = image::open(path)
+            .map_err(|e| match e {
+                ImageError::IoError(e) => e,
+                e => io::Error::new(io::ErrorKind::InvalidData, e),
+            })?
+            .to_rgba8();
+        let size = IntSize::from_wh(image.width(), image.height())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "empty image"))?;
+
+        // tiny-skia wants premultiplied alpha
+        let mut data = image.into_raw();
+        for pixel in data.chunks_exact_mut(4) {
+            let alpha = pixel[3] as u16;
+            for channel in &mut pixel[..3] {
+                *channel = ((*channel as u16 * alpha + 127) / 255) as u8;
+            }
+        }
+        let pixmap = Pixmap::from_vec(data, size)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "bad image size"))?;
+
+        Ok(Self::from_pixmap(pixmap))
+    }
+
+    fn from_pixmap(pixmap: Pixmap) -> Self {
+        Self {
+            data: Arc::new(ImageData {
+                pixmap,
+                png: OnceLock::new(),
+                fills: OnceLock::new(),
+                transformed: Mutex::default(),
+            }),
+        }
+    }
+
+    pub(crate) fn width(&self) -> f32 {
+        self.data.pixmap.width() as f32
+    }
+
+    pub(crate) fn height(&self) -> f32 {
+        self.data.pixmap.height() as f32
+    }
+
+    pub(crate) fn pixmap(&self) -> &Pixmap {
+        &self.data.pixmap
+    }
+
+    pub(crate) fn png(&self) -> &[u8] {
+        self.data
+            .png
+            .get_or_init(|| self.data.pixmap.encode_png().unwrap_or_default())
+    }
+
+    pub(crate) fn fills(&self) -> &[(canvas::Path, Color)] {
+        self.data
+            .fills
+            .get_or_init(|| pixel_fills(&self.data.pixmap))
+    }
+
+    // The picture drawn through `transform` onto whole pixels, so it can be
+    // filled in without seams, and where its top left corner goes. Only
+    // turning or stretching it draws it again; moving it doesn't.
+    pub(crate) fn transformed(&self, transform: &Transform2D<f32>) -> Option<Placed> {
+        let [a, b, c, d, e, f] = transform.to_array();
+        let moved = |(image, [x, y]): Placed| (image, [x + e, y + f]);
+        if [a, b, c, d] == [1., 0., 0., 1.] {
+            return Some((self.clone(), [e, f]));
+        }
+
+        let key = [a, b, c, d].map(f32::to_bits);
+        let mut transformed = self.data.transformed.lock().unwrap();
+        if let Some(found) = transformed.get(&key) {
+            return found.clone().map(moved);
+        }
+
+        let transform = Transform2D::new(a, b, c, d, 0., 0.);
+
+        let bounds = transform.outer_transformed_rect(&Rect::new(
+            [0., 0.].into(),
+            [self.width(), self.height()].into(),
+        ));
+        let (left, top) = (bounds.min_x().floor(), bounds.min_y().floor());
+        let width = (bounds.max_x().ceil() - left) as u32;
+        let height = (bounds.max_y().ceil() - top) as u32;
+        let image = Pixmap::new(width, height).map(|mut pixmap| {
+            let paint = PixmapPaint {
+                quality: FilterQuality::Bilinear,
+                ..PixmapPaint::default()
+            };
+            pixmap.draw_pixmap(
+                0,
+                0,
+                self.pixmap().as_ref(),
+                &paint,
+                Transform::from_row(a, b, c, d, -left, -top),
+                None,
+            );
+            (Self::from_pixmap(pixmap), [left, top])
+        });
+
+        // a turtle that's turning needs a new one every frame
+        if transformed.len() >= TRANSFORMED_LIMIT {
+            transformed.clear();
+        }
+        transformed.insert(key, image.clone());
+        image.map(moved)
+    }
+
+    // The picture as it sits behind a `width` by `height` window
+    pub(crate) fn background(&self, mode: BgPicMode, [width, height]: [isize; 2]) -> Option<Self> {
+        let mut background = Pixmap::new(width.try_into().ok()?, height.try_into().ok()?)?;
+        let (w, h) = (self.width(), self.height());
+        let paint = PixmapPaint {
+            quality: FilterQuality::Bilinear,
+            ..PixmapPaint::default()
+        };
+
+        match mode {
+            BgPicMode::Centered => {
+                let x = (width as f32 - w) / 2.;
+                let y = (height as f32 - h) / 2.;
+                background.draw_pixmap(
+                    0,
+                    0,
+                    self.pixmap().as_ref(),
+                    &paint,
+                    Transform::from_translate(x.round(), y.round()),
+                    None,
+                );
+            }
+            BgPicMode::Tiled => {
+                // tiles line up with the center of the window, like the canvas origin
+                let left = (width as f32 / 2.).rem_euclid(w) - w;
+                let top = (height as f32 / 2.).rem_euclid(h) - h;
+                let mut y = top;
+                while y < height as f32 {
+                    let mut x = left;
+                    while x < width as f32 {
+                        background.draw_pixmap(
+                            0,
+                            0,
+                            self.pixmap().as_ref(),
+                            &paint,
+                            Transform::from_translate(x, y),
+                            None,
+                        );
+                        x += w;
+                    }
+                    y += h;
+                }
+            }
+            BgPicMode::Stretched => {
+                background.draw_pixmap(
+                    0,
+                    0,
+                    self.pixmap().as_ref(),
+                    &paint,
+                    Transform::from_scale(width as f32 / w, height as f32 / h),
+                    None,
+                );
+            }
+        }
+
+        Some(Self::from_pixmap(background))
+    }
+}
+
+// red, green, blue and alpha for each pixel, without premultiplying
+pub(crate) fn rgba(pixmap: &Pixmap) -> Vec<u8> {
+    pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect()
+}
+
+fn pixel_fills(pixmap: &Pixmap) -> Vec<(canvas::Path, Color)> {
+    let mut runs: HashMap<[u8; 4], Builder> = HashMap::new();
+    let width = pixmap.width() as usize;
+
+    for (y, row) in pixmap.pixels().chunks(width).enumerate() {
+        let mut x = 0;
+        while x < width {
+            let pixel = row[x];
+            let start = x;
+            while x < width && row[x] == pixel {
+                x += 1;
+            }
+
+            if pixel.alpha() > 0 {
+                let color = pixel.demultiply();
+                let rgba = [color.red(), color.green(), color.blue(), color.alpha()];
+                runs.entry(rgba).or_default().rectangle(
+                    Point::new(start as f32, y as f32),
+                    Size::new((x - start) as f32, 1.),
+                );
+            }
+        }
+    }
+
+    runs.into_iter()
+        .map(|([r, g, b, a], builder)| {
+            (builder.build(), Color::from_rgba8(r, g, b, a as f32 / 255.))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use lyon_tessellation::geom::{euclid::default::Transform2D, Angle};
+    use tiny_skia::{Color, Pixmap};
+
+    use super::{BgPicMode, TurtleImage};
+
+    fn image(width: u32, height: u32) -> TurtleImage {
+        let mut pixmap = Pixmap::new(width, height).unwrap();
+        pixmap.fill(Color::from_rgba8(255, 0, 0, 255));
+        TurtleImage::from_pixmap(pixmap)
+    }
+
+    #[test]
+    fn moving_only_moves() {
+        let picture = image(4, 2);
+        let (moved, corner) = picture
+            .transformed(&Transform2D::translation(10.5, -3.))
+            .unwrap();
+        assert!(Arc::ptr_eq(&moved.data, &picture.data));
+        assert_eq!(corner, [10.5, -3.]);
+    }
+
+    #[test]
+    fn turned_pictures_are_drawn_once() {
+        let picture = image(4, 2);
+        let turn = Transform2D::rotation(Angle::degrees(90.));
+
+        let (first, [x0, y0]) = picture.transformed(&turn).unwrap();
+        let (second, [x1, y1]) = picture
+            .transformed(&turn.then_translate([20., 30.25].into()))
+            .unwrap();
+        assert!(Arc::ptr_eq(&first.data, &second.data));
+        assert_eq!([x1 - x0, y1 - y0], [20., 30.25]);
+        assert_eq!(picture.data.transformed.lock().unwrap().len(), 1);
+
+        // turned a quarter, the picture is as tall as it was wide
+        assert!(first.width() < first.height());
+    }
+
+    #[test]
+    fn backgrounds_fill_the_window() {
+        let picture = image(4, 2);
+        for mode in [BgPicMode::Centered, BgPicMode::Tiled, BgPicMode::Stretched] {
+            let background = picture.background(mode, [10, 6]).unwrap();
+            assert_eq!([background.width(), background.height()], [10., 6.]);
+        }
+
+        let pixel =
+            |image: &TurtleImage, x: u32, y: u32| image.pixmap().pixel(x, y).unwrap().alpha();
+        let centered = picture.background(BgPicMode::Centered, [10, 6]).unwrap();
+        assert_eq!(pixel(&centered, 5, 3), 255);
+        assert_eq!(pixel(&centered, 0, 0), 0);
+        let tiled = picture.background(BgPicMode::Tiled, [10, 6]).unwrap();
+        assert_eq!(pixel(&tiled, 0, 0), 255);
+    }
+}
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
 use iced::widget::canvas::Path;
+use lyon_tessellation::geom::euclid::default::Transform2D;
+
+use crate::{color_names::TurtleColor, fill::FillPaint, picture::TurtleImage, ScreenPosition};
 
 const CLASSIC: [[f32; 2]; 5] = [[0., 0.], [-15., 6.], [-10., 0.], [-15., -6.], [0., 0.]];
 const ARROW: [[f32; 2]; 4] = [[0., 0.], [-10., 12.], [-10., -12.], [0., 0.]];
@@ -26,8 +29,6 @@ const SQUARE: [[f32; 2]; 6] = [
 ];
 const TRIANGLE: [[f32; 2]; 4] = [[0., 0.], [-16., 8.], [-16., -8.], [0., 0.]];
 
-// TODO: add complex turtle shape
-
 const SHAPES: [(&str, &[[f32; 2]]); 5] = [
     ("classic", &CLASSIC),
     ("arrow", &ARROW),
@@ -38,19 +39,178 @@ const SHAPES: [(&str, &[[f32; 2]]); 5] = [
 
 #[derive(Debug, Clone)]
 pub struct TurtleShape {
-    pub(crate) name: String,         // TODO: make accessor
-    pub(crate) shape: TurtlePolygon, // TODO: make a get_path()
+    pub(crate) name: String, // TODO: make accessor
+    pub(crate) shape: Shape,
 }
 
 impl Default for TurtleShape {
     fn default() -> Self {
         Self {
             name: "classic".into(),
-            shape: TurtlePolygon::new(&CLASSIC),
+            shape: Shape::from_canvas(&CLASSIC),
         }
     }
 }
 
+/// A shape for `register_shape()`, made of one or more polygons. The points
+/// are in pixels, with the turtle at the origin heading east (along +x) and
+/// y pointing up, so a polygon recorded with `begin_poly()`/`get_poly()` from
+/// the home position looks the same as it was drawn.
+#[derive(Debug, Clone)]
+pub struct Shape {
+    components: Vec<ShapeComponent>,
+    image: Option<TurtleImage>, // drawn centered on the turtle, instead of any components
+    image_transform: Transform2D<f32>,
+    outline: Option<f32>, // outline width, or `None` for the pen width
+}
+
+// A color of `None` means the turtle's own fill or pen color
+#[derive(Debug, Clone)]
+pub(crate) struct ShapeComponent {
+    pub(crate) polygon: TurtlePolygon,
+    pub(crate) fill: Option<TurtleColor>,
+    pub(crate) outline: Option<TurtleColor>,
+}
+
+impl Shape {
+    /// An empty shape to add components to, as in python's `Shape("compound")`
+    pub fn compound() -> Self {
+        Self {
+            components: Vec::new(),
+            image: None,
+            image_transform: Transform2D::identity(),
+            outline: None,
+        }
+    }
+
+    /// Add a polygon drawn with its own fill and outline colors
+    pub fn addcomponent<F: Into<TurtleColor>, O: Into<TurtleColor>>(
+        &mut self,
+        poly: &[[f32; 2]],
+        fill: F,
+        outline: O,
+    ) {
+        self.components.push(ShapeComponent {
+            polygon: TurtlePolygon::new(&flip(poly)),
+            fill: Some(fill.into()),
+            outline: Some(outline.into()),
+        });
+    }
+
+    // the built-in shapes are already in canvas coordinates
+    fn from_canvas(poly: &[[f32; 2]]) -> Self {
+        Self {
+            components: vec![ShapeComponent {
+                polygon: TurtlePolygon::new(poly),
+                fill: None,
+                outline: None,
+            }],
+            image: None,
+            image_transform: Transform2D::identity(),
+            outline: None,
+        }
+    }
+
+    pub(crate) fn from_image(image: TurtleImage) -> Self {
+        Self {
+            components: Vec::new(),
+            image: Some(image),
+            image_transform: Transform2D::identity(),
+            outline: None,
+        }
+    }
+
+    // the shape as this turtle draws it, stretched, sheared and tilted
+    pub(crate) fn transformed(&self, transform: &ShapeTransform) -> Self {
+        let matrix = transform.canvas_matrix();
+        Self {
+            components: self
+                .components
+                .iter()
+                .map(|component| ShapeComponent {
+                    polygon: component.polygon.transformed(&matrix),
+                    ..component.clone()
+                })
+                .collect(),
+            image: self.image.clone(),
+            image_transform: self.image_transform.then(&matrix),
+            // halved, the same way as the pen width
+            outline: transform.outline.map(|width| width / 2.),
+        }
+    }
+
+    pub(crate) fn outline(&self) -> Option<f32> {
+        self.outline
+    }
+
+    // from the image's center to the turtle's, before turning to the heading
+    pub(crate) fn image_transform(&self) -> &Transform2D<f32> {
+        &self.image_transform
+    }
+
+    pub(crate) fn components(&self) -> &[ShapeComponent] {
+        &self.components
+    }
+
+    pub(crate) fn image(&self) -> Option<&TurtleImage> {
+        self.image.as_ref()
+    }
+
+    // read any numeric colors according to `colormode`
+    pub(crate) fn with_colormode(mut self, colormode: f32) -> Self {
+        for component in &mut self.components {
+            let resolve = |color: Option<TurtleColor>| {
+                color
+                    .map(|color| color.resolve(colormode))
+                    .filter(|color| matches!(color, TurtleColor::Color(..)))
+            };
+            component.fill = resolve(component.fill);
+            component.outline = resolve(component.outline);
+        }
+        self
+    }
+
+    pub(crate) fn contains(&self, point: ScreenPosition<f32>) -> bool {
+        if let Some(image) = &self.image {
+            let Some(point) = self
+                .image_transform
+                .inverse()
+                .map(|inverse| inverse.transform_point(point.cast_unit()))
+            else {
+                return false;
+            };
+            return point.x.abs() <= image.width() / 2. && point.y.abs() <= image.height() / 2.;
+        }
+
+        self.components
+            .iter()
+            .any(|component| component.polygon.contains(point))
+    }
+}
+
+impl From<&[[f32; 2]]> for Shape {
+    fn from(poly: &[[f32; 2]]) -> Self {
+        Self::from_canvas(&flip(poly))
+    }
+}
+
+impl From<Vec<[f32; 2]>> for Shape {
+    fn from(poly: Vec<[f32; 2]>) -> Self {
+        poly.as_slice().into()
+    }
+}
+
+impl<const N: usize> From<[[f32; 2]; N]> for Shape {
+    fn from(poly: [[f32; 2]; N]) -> Self {
+        poly.as_slice().into()
+    }
+}
+
+// y points up for the user, but down on the canvas
+fn flip(poly: &[[f32; 2]]) -> Vec<[f32; 2]> {
+    poly.iter().map(|[x, y]| [*x, -*y]).collect()
+}
+
 #[derive(Debug, Clone)]
 pub enum TurtleShapeName {
     GetCurrent,
@@ -63,29 +223,281 @@ impl From<&str> for TurtleShapeName {
     }
 }
 
+/// How a fill decides which parts of a path that crosses itself, or has
+/// holes in it, are inside
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum FillRule {
+    /// Inside if a line out from the point crosses the path an odd number of
+    /// times, as in python, so overlaps and inner rings are left empty
+    #[default]
+    EvenOdd,
+    /// Inside if the path winds around the point, so overlaps are filled and
+    /// inner rings are only empty if they're drawn the other way round
+    NonZero,
+}
+
+impl FillRule {
+    fn inside(&self, crossings: usize, winding: i32) -> bool {
+        match self {
+            FillRule::EvenOdd => crossings % 2 == 1,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+}
+
+impl From<&str> for FillRule {
+    fn from(rule: &str) -> Self {
+        match rule {
+            "nonzero" | "winding" => Self::NonZero,
+            _ => Self::EvenOdd,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TurtlePolygon {
     path: Path,
+    subpaths: Vec<Vec<[f32; 2]>>,
+    rule: FillRule,
+    paint: Option<FillPaint>, // a gradient or hatch instead of the fill color
 }
 
 impl TurtlePolygon {
     pub fn new(diagram: &[[f32; 2]]) -> Self {
-        let mut iter = diagram.iter();
+        Self::from_subpaths(vec![diagram.to_vec()], FillRule::EvenOdd)
+    }
 
-        let first = iter.next().unwrap();
+    // each subpath is closed off separately, so shape outlines get their last
+    // edge back to the start, as in python
+    pub(crate) fn from_subpaths(subpaths: Vec<Vec<[f32; 2]>>, rule: FillRule) -> Self {
         let path = Path::new(|b| {
-            b.move_to((*first).into());
-            for i in iter {
-                b.line_to((*i).into());
+            for subpath in &subpaths {
+                let mut iter = subpath.iter();
+                if let Some(first) = iter.next() {
+                    b.move_to((*first).into());
+                    for i in iter {
+                        b.line_to((*i).into());
+                    }
+                    b.close();
+                }
             }
         });
 
-        Self { path }
+        Self {
+            path,
+            subpaths,
+            rule,
+            paint: None,
+        }
+    }
+
+    pub(crate) fn with_paint(mut self, paint: Option<FillPaint>) -> Self {
+        self.paint = paint;
+        self
     }
 
     pub(crate) fn get_path(&self) -> &Path {
         &self.path
     }
+
+    pub(crate) fn rule(&self) -> FillRule {
+        self.rule
+    }
+
+    pub(crate) fn paint(&self) -> Option<&FillPaint> {
+        self.paint.as_ref()
+    }
+
+    // the lowest and highest corners of a box around every point
+    pub(crate) fn bounds(&self) -> Option<[[f32; 2]; 2]> {
+        let mut points = self.subpaths.iter().flatten();
+        let first = *points.next()?;
+        Some(points.fold([first, first], |[min, max], &[x, y]| {
+            [
+                [min[0].min(x), min[1].min(y)],
+                [max[0].max(x), max[1].max(y)],
+            ]
+        }))
+    }
+
+    fn map_points<F: Fn([f32; 2]) -> [f32; 2]>(&self, func: F) -> Self {
+        let subpaths = self
+            .subpaths
+            .iter()
+            .map(|subpath| subpath.iter().map(|&point| func(point)).collect())
+            .collect();
+        Self::from_subpaths(subpaths, self.rule)
+    }
+
+    pub(crate) fn scaled(&self, [sx, sy]: [f32; 2]) -> Self {
+        let paint = self.paint.as_ref().map(|paint| paint.scaled([sx, sy]));
+        self.map_points(|[x, y]| [x * sx, y * sy]).with_paint(paint)
+    }
+
+    pub(crate) fn transformed(&self, transform: &Transform2D<f32>) -> Self {
+        self.map_points(|[x, y]| transform.transform_point([x, y].into()).to_array())
+    }
+
+    // count the edges that a ray going right from `point` crosses, and which
+    // way round they go
+    pub(crate) fn contains(&self, point: ScreenPosition<f32>) -> bool {
+        let mut crossings = 0;
+        let mut winding = 0;
+
+        for subpath in &self.subpaths {
+            let closing = subpath.iter().cycle().skip(1);
+            for (&[x0, y0], &[x1, y1]) in subpath.iter().zip(closing) {
+                if (y0 > point.y) != (y1 > point.y)
+                    && point.x < x0 + (point.y - y0) * (x1 - x0) / (y1 - y0)
+                {
+                    crossings += 1;
+                    winding += if y1 > y0 { 1 } else { -1 };
+                }
+            }
+        }
+
+        self.rule.inside(crossings, winding)
+    }
+
+    // The stretches of the line through `origin` going `dir` that are inside
+    // the polygon, as distances along it in multiples of `dir`
+    pub(crate) fn spans(&self, origin: [f32; 2], dir: [f32; 2]) -> Vec<(f32, f32)> {
+        let side = |[x, y]: [f32; 2]| dir[0] * (y - origin[1]) - dir[1] * (x - origin[0]);
+        let along = |[x, y]: [f32; 2]| {
+            ((x - origin[0]) * dir[0] + (y - origin[1]) * dir[1])
+                / (dir[0] * dir[0] + dir[1] * dir[1])
+        };
+
+        let mut crossings = Vec::new();
+        for subpath in &self.subpaths {
+            let closing = subpath.iter().cycle().skip(1);
+            for (&a, &b) in subpath.iter().zip(closing) {
+                let (sa, sb) = (side(a), side(b));
+                if (sa > 0.) != (sb > 0.) {
+                    let s = sa / (sa - sb);
+                    let point = [a[0] + (b[0] - a[0]) * s, a[1] + (b[1] - a[1]) * s];
+                    crossings.push((along(point), if sb > sa { 1 } else { -1 }));
+                }
+            }
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut spans = Vec::new();
+        let mut winding = 0;
+        let mut start = None;
+        for (count, (t, direction)) in crossings.into_iter().enumerate() {
+            winding += direction;
+            match (start, self.rule.inside(count + 1, winding)) {
+                (None, true) => start = Some(t),
+                (Some(t0), false) => {
+                    spans.push((t0, t));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        spans
+    }
+}
+
+// How a turtle's shape is stretched, sheared and tilted, kept the way python
+// keeps it: the 2x2 matrix works on python's shape coordinates, where x is to
+// the turtle's right and y is straight ahead.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct ShapeTransform {
+    stretch: [f32; 2], // stretch_wid (across the heading), stretch_len (along it)
+    outline: Option<f32>,
+    shear: f32,
+    tilt: f32, // radians, clockwise on the screen
+    matrix: [f32; 4],
+}
+
+impl Default for ShapeTransform {
+    fn default() -> Self {
+        Self {
+            stretch: [1., 1.],
+            outline: None,
+            shear: 0.,
+            tilt: 0.,
+            matrix: [1., 0., 0., 1.],
+        }
+    }
+}
+
+impl ShapeTransform {
+    pub(crate) fn stretch(&self) -> [f32; 2] {
+        self.stretch
+    }
+
+    pub(crate) fn outline(&self) -> Option<f32> {
+        self.outline
+    }
+
+    pub(crate) fn shear(&self) -> f32 {
+        self.shear
+    }
+
+    pub(crate) fn tilt(&self) -> f32 {
+        self.tilt
+    }
+
+    pub(crate) fn matrix(&self) -> [f32; 4] {
+        self.matrix
+    }
+
+    pub(crate) fn set_stretch(&mut self, stretch: [f32; 2]) {
+        self.stretch = stretch;
+        self.update_matrix();
+    }
+
+    pub(crate) fn set_outline(&mut self, outline: Option<f32>) {
+        self.outline = outline;
+    }
+
+    pub(crate) fn set_shear(&mut self, shear: f32) {
+        self.shear = shear;
+        self.update_matrix();
+    }
+
+    pub(crate) fn set_tilt(&mut self, tilt: f32) {
+        self.tilt = tilt.rem_euclid(std::f32::consts::TAU);
+        self.update_matrix();
+    }
+
+    fn update_matrix(&mut self) {
+        let [scx, scy] = self.stretch;
+        let (sa, ca) = self.tilt.sin_cos();
+        self.matrix = [
+            scx * ca,
+            scy * (self.shear * ca + sa),
+            -scx * sa,
+            scy * (ca - self.shear * sa),
+        ];
+    }
+
+    // Take the stretch, shear and tilt back out of the matrix
+    pub(crate) fn set_matrix(&mut self, matrix: [f32; 4]) {
+        let [m11, m12, m21, m22] = matrix;
+        self.matrix = matrix;
+
+        let tilt = (-m21).atan2(m11).rem_euclid(std::f32::consts::TAU);
+        let (sa, ca) = tilt.sin_cos();
+        let (a11, a12, a22) = (
+            ca * m11 - sa * m21,
+            ca * m12 - sa * m22,
+            sa * m12 + ca * m22,
+        );
+        self.stretch = [a11, a22];
+        self.shear = a12 / a22;
+        self.tilt = tilt;
+    }
+
+    // The same matrix on the canvas, where x is straight ahead and y is to the
+    // turtle's right
+    fn canvas_matrix(&self) -> Transform2D<f32> {
+        let [t11, t12, t21, t22] = self.matrix;
+        Transform2D::new(t22, t12, t21, t11, 0., 0.)
+    }
 }
 
 pub(crate) fn generate_default_shapes() -> HashMap<String, TurtleShape> {
@@ -96,10 +508,140 @@ pub(crate) fn generate_default_shapes() -> HashMap<String, TurtleShape> {
             (*name).into(),
             TurtleShape {
                 name: (*name).into(),
-                shape: TurtlePolygon::new(poly),
+                shape: Shape::from_canvas(poly),
             },
         );
     }
 
     shapes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &[f32], b: &[f32]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b) {
+            assert!((x - y).abs() < 1e-4, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn default_transform_is_identity() {
+        let transform = ShapeTransform::default();
+        assert_eq!(transform.matrix(), [1., 0., 0., 1.]);
+        assert_eq!(transform.stretch(), [1., 1.]);
+    }
+
+    #[test]
+    fn set_matrix_round_trip() {
+        let mut transform = ShapeTransform::default();
+        transform.set_stretch([2., 0.5]);
+        transform.set_shear(0.75);
+        transform.set_tilt(1.2);
+
+        let mut copy = ShapeTransform::default();
+        copy.set_matrix(transform.matrix());
+        assert_close(&copy.stretch(), &transform.stretch());
+        assert_close(&[copy.shear()], &[transform.shear()]);
+        assert_close(&[copy.tilt()], &[transform.tilt()]);
+        assert_close(&copy.matrix(), &transform.matrix());
+    }
+
+    #[test]
+    fn set_matrix_keeps_the_matrix() {
+        let matrix = [1.5, -0.25, 0.5, 3.];
+        let mut transform = ShapeTransform::default();
+        transform.set_matrix(matrix);
+        assert_eq!(transform.matrix(), matrix);
+
+        // rebuilding the matrix from the parts taken out of it gives it back
+        transform.set_tilt(transform.tilt());
+        assert_close(&transform.matrix(), &matrix);
+    }
+
+    #[test]
+    fn tilt_wraps_around() {
+        let mut transform = ShapeTransform::default();
+        transform.set_tilt(-std::f32::consts::FRAC_PI_2);
+        assert_close(&[transform.tilt()], &[3. * std::f32::consts::FRAC_PI_2]);
+    }
+
+    fn ring(inner_reversed: bool, rule: FillRule) -> TurtlePolygon {
+        let outer = vec![[0., 0.], [10., 0.], [10., 10.], [0., 10.]];
+        let mut inner = vec![[3., 3.], [7., 3.], [7., 7.], [3., 7.]];
+        if inner_reversed {
+            inner.reverse();
+        }
+        TurtlePolygon::from_subpaths(vec![outer, inner], rule)
+    }
+
+    #[test]
+    fn fill_rule_names() {
+        assert_eq!(FillRule::from("evenodd"), FillRule::EvenOdd);
+        assert_eq!(FillRule::from("nonzero"), FillRule::NonZero);
+        assert_eq!(FillRule::from("winding"), FillRule::NonZero);
+        assert_eq!(FillRule::default(), FillRule::EvenOdd);
+    }
+
+    #[test]
+    fn even_odd_leaves_holes() {
+        let hole = ScreenPosition::new(5., 5.);
+        let band = ScreenPosition::new(1., 5.);
+        let outside = ScreenPosition::new(11., 5.);
+
+        for reversed in [false, true] {
+            let poly = ring(reversed, FillRule::EvenOdd);
+            assert!(!poly.contains(hole));
+            assert!(poly.contains(band));
+            assert!(!poly.contains(outside));
+        }
+    }
+
+    #[test]
+    fn non_zero_follows_the_winding() {
+        let hole = ScreenPosition::new(5., 5.);
+        let band = ScreenPosition::new(1., 5.);
+
+        let poly = ring(false, FillRule::NonZero);
+        assert!(poly.contains(hole));
+        assert!(poly.contains(band));
+
+        let poly = ring(true, FillRule::NonZero);
+        assert!(!poly.contains(hole));
+        assert!(poly.contains(band));
+    }
+
+    #[test]
+    fn overlapping_star() {
+        // a five pointed star drawn in one stroke overlaps itself in the middle
+        let star: Vec<[f32; 2]> = (0..5)
+            .map(|i| {
+                let angle = (i * 144) as f32 * std::f32::consts::PI / 180.;
+                [angle.cos() * 10., angle.sin() * 10.]
+            })
+            .collect();
+        let middle = ScreenPosition::new(0., 0.);
+
+        let poly = TurtlePolygon::from_subpaths(vec![star.clone()], FillRule::EvenOdd);
+        assert!(!poly.contains(middle));
+        let poly = TurtlePolygon::from_subpaths(vec![star], FillRule::NonZero);
+        assert!(poly.contains(middle));
+    }
+
+    #[test]
+    fn spans_skip_holes() {
+        let spans = ring(false, FillRule::EvenOdd).spans([-5., 5.], [1., 0.]);
+        assert_close(
+            &spans.iter().flat_map(|&(a, b)| [a, b]).collect::<Vec<_>>(),
+            &[5., 8., 12., 15.],
+        );
+
+        let spans = ring(false, FillRule::NonZero).spans([-5., 5.], [1., 0.]);
+        assert_close(
+            &spans.iter().flat_map(|&(a, b)| [a, b]).collect::<Vec<_>>(),
+            &[5., 15.],
+        );
+    }
+}
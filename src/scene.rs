@@ -0,0 +1,160 @@
+mod png;
+mod svg;
+
+use std::path::Path;
+
+use crate::{
+    color_names::TurtleColor,
+    command::ScreenCmd,
+    comms::Response,
+    generate::DrawCommand,
+    picture::{self, BgPicMode, TurtleImage},
+    polygon::TurtleShape,
+    screen::Screen,
+    turtle::Turtle,
+};
+
+/// A snapshot of everything on the canvas. Turtles are listed in the order
+/// they were created, which is also the order they're drawn in.
+#[derive(Debug, Clone)]
+pub struct Scene {
+    pub bgcolor: TurtleColor,
+    pub size: [isize; 2],
+    pub turtles: Vec<SceneTurtle>,
+    pub(crate) bgpic: Option<(TurtleImage, BgPicMode)>,
+    pub(crate) antialias: bool,
+}
+
+/// Everything a single turtle has drawn so far.
+#[derive(Debug, Clone)]
+pub struct SceneTurtle {
+    pub commands: Vec<DrawCommand>,
+    // indexes into `commands` of the stamps still on screen; these aren't the
+    // ids that `stamp()` returns
+    pub stamps: Vec<usize>,
+    pub shape: String,
+    pub visible: bool,
+    pub(crate) turtle_shape: TurtleShape,
+}
+
+impl SceneTurtle {
+    pub(crate) fn new(
+        commands: Vec<DrawCommand>,
+        turtle_shape: TurtleShape,
+        visible: bool,
+    ) -> Self {
+        let stamps = commands
+            .iter()
+            .enumerate()
+            .filter(|(_, cmd)| matches!(cmd, DrawCommand::DrawPolyAt(_, _, _)))
+            .map(|(idx, _)| idx)
+            .collect();
+
+        Self {
+            commands,
+            stamps,
+            shape: turtle_shape.name.clone(),
+            visible,
+            turtle_shape,
+        }
+    }
+}
+
+impl Scene {
+    pub(crate) fn new(
+        bgcolor: TurtleColor,
+        bgpic: Option<(TurtleImage, BgPicMode)>,
+        size: [isize; 2],
+        turtles: Vec<SceneTurtle>,
+    ) -> Self {
+        Self {
+            bgcolor,
+            size,
+            turtles,
+            bgpic,
+            antialias: true,
+        }
+    }
+
+    /// Choose whether `to_rgba()` and `save_png()` smooth their edges (the default)
+    pub fn with_antialiasing(mut self, antialias: bool) -> Self {
+        self.antialias = antialias;
+        self
+    }
+
+    /// Render the drawing as an SVG document. The turtle sprites themselves
+    /// are not included, only what they've drawn.
+    pub fn to_svg(&self) -> String {
+        svg::SvgWriter::new(self).finish()
+    }
+
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_svg())
+    }
+
+    /// Rasterize the canvas, turtles included, at the window size. The result
+    /// is `width * height` RGBA pixels, one row after the other.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        png::rasterize(self)
+            .map(|pixmap| picture::rgba(&pixmap))
+            .unwrap_or_default()
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let pixmap = png::rasterize(self).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "canvas has no area")
+        })?;
+        let data = pixmap.encode_png().map_err(std::io::Error::other)?;
+        std::fs::write(path, data)
+    }
+}
+
+impl Screen {
+    /// Take a snapshot of the canvas as it is right now.
+    pub fn scene(&self) -> Scene {
+        if let Response::Scene(scene) = self.do_command(ScreenCmd::Scene) {
+            scene
+        } else {
+            panic!("invalid response from screen");
+        }
+    }
+
+    pub fn to_svg(&self) -> String {
+        self.scene().to_svg()
+    }
+
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.scene().save_svg(path)
+    }
+
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.scene().to_rgba()
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.scene().save_png(path)
+    }
+}
+
+impl Turtle {
+    /// Take a snapshot of the canvas as it is right now.
+    pub fn scene(&self) -> Scene {
+        self.screen().scene()
+    }
+
+    pub fn to_svg(&self) -> String {
+        self.scene().to_svg()
+    }
+
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.scene().save_svg(path)
+    }
+
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.scene().to_rgba()
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        self.scene().save_png(path)
+    }
+}
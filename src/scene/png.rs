@@ -0,0 +1,193 @@
+use iced::widget::canvas::Path;
+use lyon_tessellation::path::Event;
+use tiny_skia::{
+    Color, FillRule, FilterQuality, GradientStop, LineCap, LineJoin, LinearGradient, Paint,
+    PathBuilder, Pixmap, PixmapPaint, Point, RadialGradient, Shader, SpreadMode, Stroke, Transform,
+};
+
+use super::Scene;
+use crate::{
+    fill::CanvasGradient,
+    gui::iced_gui::{convert_commands, IcedDrawCmd},
+    polygon, stroke,
+};
+
+// Draw the scene on the CPU, using the same paths that the iced canvas gets.
+pub(crate) fn rasterize(scene: &Scene) -> Option<Pixmap> {
+    let [width, height] = scene.size;
+    let mut pixmap = Pixmap::new(width.try_into().ok()?, height.try_into().ok()?)?;
+
+    let bgcolor: iced::Color = (&scene.bgcolor).into();
+    pixmap.fill(skia_color(bgcolor));
+
+    if let Some(background) = scene
+        .bgpic
+        .as_ref()
+        .and_then(|(image, mode)| image.background(*mode, scene.size))
+    {
+        pixmap.draw_pixmap(
+            0,
+            0,
+            background.pixmap().as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            None,
+        );
+    }
+
+    let center = Transform::from_translate(width as f32 / 2., height as f32 / 2.);
+
+    for turtle in &scene.turtles {
+        let drawing = convert_commands(
+            &turtle.commands,
+            1.,
+            [1., 1.],
+            &turtle.turtle_shape,
+            !turtle.visible,
+        );
+
+        for cmd in &drawing {
+            match cmd {
+                IcedDrawCmd::Stroke(path, pencolor, penwidth, style) => {
+                    if let Some(path) = skia_path(path) {
+                        let stroke = Stroke {
+                            width: *penwidth,
+                            line_cap: match style.cap {
+                                stroke::LineCap::Butt => LineCap::Butt,
+                                stroke::LineCap::Round => LineCap::Round,
+                                stroke::LineCap::Square => LineCap::Square,
+                            },
+                            line_join: match style.join {
+                                stroke::LineJoin::Miter => LineJoin::Miter,
+                                stroke::LineJoin::Round => LineJoin::Round,
+                                stroke::LineJoin::Bevel => LineJoin::Bevel,
+                            },
+                            ..Stroke::default()
+                        };
+                        pixmap.stroke_path(
+                            &path,
+                            &paint(*pencolor, scene.antialias),
+                            &stroke,
+                            center,
+                            None,
+                        );
+                    }
+                }
+                IcedDrawCmd::Text(text) => text.draw_with(|path, color| {
+                    if let Some(path) = skia_path(&path) {
+                        pixmap.fill_path(
+                            &path,
+                            &paint(color, scene.antialias),
+                            FillRule::Winding,
+                            center,
+                            None,
+                        );
+                    }
+                }),
+                IcedDrawCmd::Image(image, xform) => {
+                    let paint = PixmapPaint {
+                        quality: FilterQuality::Bilinear,
+                        ..PixmapPaint::default()
+                    };
+                    let xform = Transform::from_row(
+                        xform.m11, xform.m12, xform.m21, xform.m22, xform.m31, xform.m32,
+                    );
+                    pixmap.draw_pixmap(
+                        0,
+                        0,
+                        image.pixmap().as_ref(),
+                        &paint,
+                        center.pre_concat(xform),
+                        None,
+                    );
+                }
+                IcedDrawCmd::Fill(path, fillcolor, rule) => {
+                    if let Some(path) = skia_path(path) {
+                        pixmap.fill_path(
+                            &path,
+                            &paint(*fillcolor, scene.antialias),
+                            skia_rule(*rule),
+                            center,
+                            None,
+                        );
+                    }
+                }
+                IcedDrawCmd::Gradient(path, gradient, rule) => {
+                    if let Some(path) = skia_path(path) {
+                        let paint = Paint {
+                            shader: shader(gradient),
+                            anti_alias: scene.antialias,
+                            ..Paint::default()
+                        };
+                        pixmap.fill_path(&path, &paint, skia_rule(*rule), center, None);
+                    }
+                }
+            }
+        }
+    }
+
+    Some(pixmap)
+}
+
+fn skia_color(color: iced::Color) -> Color {
+    Color::from_rgba(color.r, color.g, color.b, color.a).unwrap_or(Color::BLACK)
+}
+
+fn paint(color: iced::Color, anti_alias: bool) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(skia_color(color));
+    paint.anti_alias = anti_alias;
+    paint
+}
+
+fn skia_rule(rule: polygon::FillRule) -> FillRule {
+    match rule {
+        polygon::FillRule::EvenOdd => FillRule::EvenOdd,
+        polygon::FillRule::NonZero => FillRule::Winding,
+    }
+}
+
+// gradients are drawn as they are, rather than the way the canvas makes do
+fn shader(gradient: &CanvasGradient) -> Shader<'static> {
+    let stops: Vec<_> = gradient
+        .stops()
+        .iter()
+        .map(|(offset, color)| GradientStop::new(*offset, skia_color(color.into())))
+        .collect();
+    let last = skia_color((&gradient.color_at(1.)).into());
+
+    let shader = if let Some((start, end)) = gradient.linear() {
+        let (start, end) = (
+            Point::from_xy(start.x, start.y),
+            Point::from_xy(end.x, end.y),
+        );
+        LinearGradient::new(start, end, stops, SpreadMode::Pad, Transform::identity())
+    } else if let Some((center, radius)) = gradient.radial() {
+        let center = Point::from_xy(center.x, center.y);
+        let mode = SpreadMode::Pad;
+        RadialGradient::new(center, center, radius, stops, mode, Transform::identity())
+    } else {
+        None
+    };
+    // a gradient with no length is all its last color, as in SVG
+    shader.unwrap_or(Shader::SolidColor(last))
+}
+
+fn skia_path(path: &Path) -> Option<tiny_skia::Path> {
+    let mut builder = PathBuilder::new();
+
+    for event in path.raw() {
+        match event {
+            Event::Begin { at } => builder.move_to(at.x, at.y),
+            Event::Line { to, .. } => builder.line_to(to.x, to.y),
+            Event::Quadratic { ctrl, to, .. } => builder.quad_to(ctrl.x, ctrl.y, to.x, to.y),
+            Event::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => builder.cubic_to(ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y),
+            Event::End { close: true, .. } => builder.close(),
+            Event::End { .. } => {}
+        }
+    }
+
+    builder.finish()
+}
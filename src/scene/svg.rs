@@ -0,0 +1,337 @@
+use std::fmt::Write;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use iced::{widget::canvas::Path, Color, Point};
+use lyon_tessellation::{
+    geom::{euclid::default::Transform2D, Angle},
+    path::Event,
+};
+
+use super::{Scene, SceneTurtle};
+use crate::{
+    color_names::TurtleColor,
+    fill::{CanvasGradient, FillPaint, HatchKind},
+    generate::DrawCommand,
+    picture::TurtleImage,
+    polygon::FillRule,
+    stroke::{Dash, LineCap, LineJoin, StrokeStyle},
+};
+
+// Walks the draw commands the same way `IndividualTurtle::convert` does for the
+// iced canvas, so that the exported picture matches what's on the screen.
+pub(crate) struct SvgWriter {
+    out: String,
+    gradients: usize, // so far, to give each one its own id
+}
+
+impl SvgWriter {
+    pub(crate) fn new(scene: &Scene) -> Self {
+        let [width, height] = scene.size;
+        let (left, top) = (-width as f32 / 2., -height as f32 / 2.);
+
+        let mut this = Self {
+            out: String::new(),
+            gradients: 0,
+        };
+        let _ = writeln!(
+            this.out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="{left} {top} {width} {height}">"#
+        );
+        if let Some(bgcolor) = color(&scene.bgcolor) {
+            let _ = writeln!(
+                this.out,
+                r#"<rect x="{left}" y="{top}" width="{width}" height="{height}" fill="{bgcolor}"/>"#
+            );
+        }
+        if let Some(background) = scene
+            .bgpic
+            .as_ref()
+            .and_then(|(image, mode)| image.background(*mode, scene.size))
+        {
+            let data = data_url(&background);
+            let _ = writeln!(
+                this.out,
+                r#"<image x="{left}" y="{top}" width="{width}" height="{height}" href="{data}"/>"#
+            );
+        }
+
+        for turtle in &scene.turtles {
+            this.turtle(turtle);
+        }
+
+        this
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        self.out.push_str("</svg>\n");
+        self.out
+    }
+
+    fn turtle(&mut self, turtle: &SceneTurtle) {
+        let mut pencolor = String::from("rgb(0,0,0)");
+        let mut penwidth = 1.0;
+        let mut style = StrokeStyle::default();
+        let mut dash = Dash::default();
+        let mut fillcolor = String::from("rgb(0,0,0)");
+
+        let mut cur_path = String::new();
+
+        for element in &turtle.commands {
+            if !matches!(element, DrawCommand::Line(..))
+                && !matches!(element, DrawCommand::SetHeading(..))
+                && !cur_path.is_empty()
+            {
+                self.stroke(&cur_path, &pencolor, penwidth, style, &dash);
+                cur_path.clear();
+            }
+
+            match element {
+                DrawCommand::Line(l) => {
+                    if cur_path.is_empty() {
+                        let _ = write!(cur_path, "M{} {}", l.begin.x, l.begin.y);
+                    }
+                    let op = if l.pen_down { 'L' } else { 'M' };
+                    let _ = write!(cur_path, " {op}{} {}", l.end.x, l.end.y);
+                }
+                DrawCommand::SetPenColor(pc) => {
+                    if let Some(pc) = color(pc) {
+                        pencolor = pc;
+                    }
+                }
+                DrawCommand::SetPenWidth(pw) => penwidth = *pw,
+                DrawCommand::SetDash(pattern, offset) => dash = Dash::new(pattern, *offset),
+                DrawCommand::SetLineCap(cap) => style.cap = *cap,
+                DrawCommand::SetLineJoin(join) => style.join = *join,
+                DrawCommand::SetFillColor(fc) => {
+                    if let Some(fc) = color(fc) {
+                        fillcolor = fc;
+                    }
+                }
+                DrawCommand::DrawPolygon(p) => {
+                    let data = path_data(p.get_path());
+                    match p.paint() {
+                        None => self.fill(&data, &fillcolor, p.rule()),
+                        Some(FillPaint::Gradient(gradient)) => {
+                            let url = self.gradient(gradient);
+                            self.fill(&data, &url, p.rule());
+                        }
+                        Some(FillPaint::Hatch(hatch)) => {
+                            if let Some(background) = hatch.background().and_then(color) {
+                                self.fill(&data, &background, p.rule());
+                            }
+                            if let Some(color) = hatch.color().and_then(color) {
+                                // the same marks as on the screen
+                                let marks = path_data(&hatch.marks(p));
+                                if hatch.kind() == HatchKind::Dots {
+                                    self.fill(&marks, &color, FillRule::NonZero);
+                                } else {
+                                    let (width, style) = (hatch.width(), StrokeStyle::default());
+                                    self.stroke(&marks, &color, width, style, &Dash::default());
+                                }
+                            }
+                        }
+                    }
+                }
+                DrawCommand::SetHeading(..) => {}
+                DrawCommand::DrawDot(center, radius, dotcolor) => {
+                    if let Some(dotcolor) = color(dotcolor) {
+                        let _ = writeln!(
+                            self.out,
+                            r#"<circle cx="{}" cy="{}" r="{radius}" fill="{dotcolor}"/>"#,
+                            center.x, center.y
+                        );
+                    }
+                }
+                DrawCommand::DrawText(pos, text, align, font) => {
+                    // glyphs are written out as paths so the picture doesn't depend
+                    // on what fonts the viewer has installed
+                    let pos = Point::new(pos.x, pos.y);
+                    font.text(text, pos, Color::BLACK, *align)
+                        .draw_with(|path, _| {
+                            let data = path_data(&path);
+                            let _ = writeln!(self.out, r#"<path d="{data}" fill="{pencolor}"/>"#);
+                        });
+                    if font.underline {
+                        let (start, end, width) = font.underline(text, pos, *align);
+                        let data = format!("M{} {} L{} {}", start.x, start.y, end.x, end.y);
+                        let solid = Dash::default();
+                        self.stroke(&data, &pencolor, width, StrokeStyle::default(), &solid);
+                    }
+                }
+                DrawCommand::DrawPolyAt(shape, pos, angle) => {
+                    let xform = Transform2D::rotation(Angle::degrees(*angle))
+                        .then_translate([pos.x, pos.y].into());
+                    if let Some(image) = shape.image() {
+                        let (width, height) = (image.width(), image.height());
+                        let corner = Transform2D::translation(-width / 2., -height / 2.);
+                        let m = corner.then(shape.image_transform()).then(&xform);
+                        let data = data_url(image);
+                        let _ = writeln!(
+                            self.out,
+                            r#"<image width="{width}" height="{height}" transform="matrix({} {} {} {} {} {})" href="{data}"/>"#,
+                            m.m11, m.m12, m.m21, m.m22, m.m31, m.m32
+                        );
+                    }
+                    let width = shape.outline().unwrap_or(penwidth);
+                    for component in shape.components() {
+                        let data = path_data(&component.polygon.get_path().transform(&xform));
+                        let fill = component.fill.as_ref().and_then(color);
+                        let outline = component.outline.as_ref().and_then(color);
+                        let fill = fill.as_ref().unwrap_or(&fillcolor);
+                        self.fill(&data, fill, FillRule::EvenOdd);
+                        let outline = outline.as_ref().unwrap_or(&pencolor);
+                        let solid = Dash::default();
+                        self.stroke(&data, outline, width, StrokeStyle::default(), &solid);
+                    }
+                }
+                DrawCommand::Circle(points) => {
+                    if points[0].pen_down {
+                        let mut data = String::new();
+                        for (idx, p) in points.iter().enumerate() {
+                            let op = if idx == 0 { 'M' } else { 'L' };
+                            let _ = write!(data, "{op}{} {} ", p.x, p.y);
+                        }
+                        self.stroke(data.trim_end(), &pencolor, penwidth, style, &dash);
+                    }
+                }
+                DrawCommand::Filler
+                | DrawCommand::Filled(_)
+                | DrawCommand::StampTurtle
+                | DrawCommand::BeginFill
+                | DrawCommand::EndFill
+                | DrawCommand::BeginPoly
+                | DrawCommand::EndPoly => {}
+            }
+        }
+
+        if !cur_path.is_empty() {
+            self.stroke(&cur_path, &pencolor, penwidth, style, &dash);
+        }
+    }
+
+    fn stroke(
+        &mut self,
+        data: &str,
+        pencolor: &str,
+        penwidth: f32,
+        style: StrokeStyle,
+        dash: &Dash,
+    ) {
+        if !data.contains('L') {
+            // only pen-up moves; nothing would be visible
+            return;
+        }
+        let cap = match style.cap {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        };
+        let join = match style.join {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        };
+        let mut attrs = format!(r#"stroke-linecap="{cap}" stroke-linejoin="{join}""#);
+        if !dash.pattern().is_empty() {
+            let pattern: Vec<String> = dash.pattern().iter().map(f32::to_string).collect();
+            let _ = write!(
+                attrs,
+                r#" stroke-dasharray="{}" stroke-dashoffset="{}""#,
+                pattern.join(" "),
+                dash.offset()
+            );
+        }
+        let _ = writeln!(
+            self.out,
+            r#"<path d="{data}" fill="none" stroke="{pencolor}" stroke-width="{penwidth}" {attrs}/>"#
+        );
+    }
+
+    // Define a gradient, and return what to fill with to use it
+    fn gradient(&mut self, gradient: &CanvasGradient) -> String {
+        self.gradients += 1;
+        let id = format!("gradient{}", self.gradients);
+
+        let (tag, attrs) = if let Some((start, end)) = gradient.linear() {
+            let attrs = format!(
+                r#"x1="{}" y1="{}" x2="{}" y2="{}""#,
+                start.x, start.y, end.x, end.y
+            );
+            ("linearGradient", attrs)
+        } else if let Some((center, radius)) = gradient.radial() {
+            let attrs = format!(r#"cx="{}" cy="{}" r="{radius}""#, center.x, center.y);
+            ("radialGradient", attrs)
+        } else {
+            return String::from("none");
+        };
+
+        let _ = writeln!(
+            self.out,
+            r#"<defs><{tag} id="{id}" gradientUnits="userSpaceOnUse" {attrs}>"#
+        );
+        for (offset, stop) in gradient.stops() {
+            if let Some(stop) = color(stop) {
+                let _ = writeln!(self.out, r#"<stop offset="{offset}" stop-color="{stop}"/>"#);
+            }
+        }
+        let _ = writeln!(self.out, "</{tag}></defs>");
+
+        format!("url(#{id})")
+    }
+
+    fn fill(&mut self, data: &str, fillcolor: &str, rule: FillRule) {
+        let rule = match rule {
+            FillRule::EvenOdd => "evenodd",
+            FillRule::NonZero => "nonzero",
+        };
+        let _ = writeln!(
+            self.out,
+            r#"<path d="{data}" fill="{fillcolor}" fill-rule="{rule}"/>"#
+        );
+    }
+}
+
+fn color(color: &TurtleColor) -> Option<String> {
+    match color {
+        TurtleColor::CurrentColor | TurtleColor::Unscaled(..) => None,
+        TurtleColor::Color(r, g, b, a) => {
+            let r = (r * 255.).round() as u8;
+            let g = (g * 255.).round() as u8;
+            let b = (b * 255.).round() as u8;
+            if *a < 1. {
+                Some(format!("rgba({r},{g},{b},{a})"))
+            } else {
+                Some(format!("rgb({r},{g},{b})"))
+            }
+        }
+    }
+}
+
+fn data_url(image: &TurtleImage) -> String {
+    format!("data:image/png;base64,{}", STANDARD.encode(image.png()))
+}
+
+fn path_data(path: &Path) -> String {
+    let mut data = String::new();
+
+    for event in path.raw() {
+        let _ = match event {
+            Event::Begin { at } => write!(data, "M{} {} ", at.x, at.y),
+            Event::Line { to, .. } => write!(data, "L{} {} ", to.x, to.y),
+            Event::Quadratic { ctrl, to, .. } => {
+                write!(data, "Q{} {} {} {} ", ctrl.x, ctrl.y, to.x, to.y)
+            }
+            Event::Cubic {
+                ctrl1, ctrl2, to, ..
+            } => write!(
+                data,
+                "C{} {} {} {} {} {} ",
+                ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y
+            ),
+            Event::End { close: true, .. } => write!(data, "Z "),
+            Event::End { .. } => Ok(()),
+        };
+    }
+
+    data.trim_end().to_string()
+}
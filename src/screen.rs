@@ -0,0 +1,366 @@
+use std::{
+    io,
+    path::Path,
+    sync::mpsc::{self, Sender},
+};
+
+use crate::{
+    color_names::TurtleColor,
+    command::ScreenCmd,
+    comms::{Request, Response},
+    coords::TurtleMode,
+    picture::{BgPicMode, TurtleImage},
+    polygon::Shape,
+    turtle::Turtle,
+};
+
+/// The window the turtles draw in, for everything that belongs to the whole
+/// screen rather than to one turtle. Get one from `Turtle::screen()`; it can
+/// be cloned and used from any thread.
+#[derive(Debug, Clone)]
+pub struct Screen {
+    issue_command: Sender<Request>,
+}
+
+impl Screen {
+    pub(crate) fn new(issue_command: Sender<Request>) -> Self {
+        Self { issue_command }
+    }
+
+    pub(crate) fn do_command(&self, cmd: ScreenCmd) -> Response {
+        let (reply, response) = mpsc::channel();
+        if self.issue_command.send(Request::Screen(cmd, reply)).is_ok() {
+            if let Ok(response) = response.recv() {
+                return response;
+            }
+        }
+
+        /* main thread has gone away; wait here to meet our doom */
+        loop {
+            std::thread::park();
+        }
+    }
+
+    pub fn bgcolor<C: Into<TurtleColor>>(&self, color: C) {
+        self.do_command(ScreenCmd::Background(color.into()));
+    }
+
+    /// Erase what every turtle has drawn and reset every turtle, as python does.
+    /// The screen goes back to how it started: a white background with no
+    /// picture, no event bindings, standard mode, `colormode(1.0)` and tracing on.
+    pub fn clearscreen(&self) {
+        self.do_command(ScreenCmd::ClearScreen);
+    }
+
+    /// Reset every turtle, as `Turtle::reset()` does
+    pub fn resetscreen(&self) {
+        self.do_command(ScreenCmd::ResetScreen);
+    }
+
+    pub fn screensize<S: Into<[isize; 2]>>(&self, s: S) {
+        self.do_command(ScreenCmd::SetSize(s.into()));
+    }
+
+    /// Switch between "standard", "logo" and "world" coordinates. Unlike python,
+    /// this doesn't reset the turtles.
+    pub fn mode<M: Into<TurtleMode>>(&self, mode: M) {
+        self.do_command(ScreenCmd::Mode(mode.into()));
+    }
+
+    /// Map the window onto the rectangle from (`llx`, `lly`) at the lower left
+    /// to (`urx`, `ury`) at the upper right, and switch to world mode. The
+    /// rectangle keeps filling the window when it's resized.
+    pub fn setworldcoordinates<A, B, C, D>(&self, llx: A, lly: B, urx: C, ury: D)
+    where
+        A: Into<f64>,
+        B: Into<f64>,
+        C: Into<f64>,
+        D: Into<f64>,
+    {
+        let lower_left = [llx.into() as f32, lly.into() as f32];
+        let upper_right = [urx.into() as f32, ury.into() as f32];
+        assert!(
+            lower_left[0] != upper_right[0] && lower_left[1] != upper_right[1],
+            "world coordinates must cover some area"
+        );
+        self.do_command(ScreenCmd::WorldCoordinates(lower_left, upper_right));
+    }
+
+    /// Whether numeric colors like `(0.5, 0.5, 1.0)` run up to 1.0 or up to
+    /// 255, as in python's `colormode()`. Alpha always runs from 0 to 1.
+    pub fn colormode<N: Into<f64>>(&self, colormode: N) {
+        let colormode = colormode.into() as f32;
+        assert!(
+            colormode == 1. || colormode == 255.,
+            "colormode must be 1.0 or 255"
+        );
+        self.do_command(ScreenCmd::ColorMode(colormode));
+    }
+
+    /// Add a shape that any turtle can switch to with `shape(name)`. It may be a
+    /// list of points, such as the output of `get_poly()`, or a compound `Shape`.
+    pub fn register_shape<S: Into<Shape>>(&self, name: &str, shape: S) {
+        self.do_command(ScreenCmd::RegisterShape(name.into(), shape.into()));
+    }
+
+    /// Add a shape drawn from a PNG or GIF file. The picture is centered on the
+    /// turtle, and turned with its heading: it's drawn as-is when heading east.
+    pub fn register_image_shape<P: AsRef<Path>>(&self, name: &str, path: P) -> io::Result<()> {
+        let image = TurtleImage::open(path)?;
+        self.do_command(ScreenCmd::RegisterShape(
+            name.into(),
+            Shape::from_image(image),
+        ));
+        Ok(())
+    }
+
+    /// Show a PNG or GIF picture behind the drawing, placed as in `BgPicMode`.
+    /// The name "nopic" removes it again.
+    pub fn bgpic<P: AsRef<Path>, M: Into<BgPicMode>>(
+        &self,
+        path: P,
+        placement: M,
+    ) -> io::Result<()> {
+        let bgpic = if path.as_ref() == Path::new("nopic") {
+            None
+        } else {
+            Some((TurtleImage::open(path)?, placement.into()))
+        };
+        self.do_command(ScreenCmd::BgPic(bgpic));
+        Ok(())
+    }
+
+    /// Show only every `n`-th update of the turtles, which speeds drawing up
+    /// `n` times. With 0, turtles finish their commands at once and the window
+    /// keeps showing what it showed until `update()`, so a whole frame can be
+    /// drawn before it appears. A `delay` in milliseconds also sets `delay()`.
+    ///
+    /// While tracing is off, no turtle waits for its commands to be drawn, so
+    /// they return straight away.
+    pub fn tracer<D: Into<Option<u64>>>(&self, n: usize, delay: D) {
+        self.do_command(ScreenCmd::Tracer(n, delay.into()));
+    }
+
+    /// The time between updates in milliseconds, 10 by default as in python.
+    /// Longer delays slow the turtles down.
+    pub fn delay(&self, delay: u64) {
+        self.do_command(ScreenCmd::Delay(delay));
+    }
+
+    /// Show everything drawn so far, when `tracer(0)` has turned tracing off.
+    /// Returns once the window shows it; drawing after this goes into the
+    /// next frame.
+    pub fn update(&self) {
+        self.do_command(ScreenCmd::Update);
+    }
+
+    /// Every turtle on the screen, in the order they were created. Each one
+    /// is a new handle, so it can be moved to another thread.
+    pub fn turtles(&self) -> Vec<Turtle> {
+        if let Response::Turtles(turtles) = self.do_command(ScreenCmd::Turtles) {
+            turtles
+        } else {
+            panic!("invalid response from screen");
+        }
+    }
+
+    pub fn getmode(&self) -> TurtleMode {
+        if let Response::Mode(mode) = self.do_command(ScreenCmd::GetMode) {
+            mode
+        } else {
+            panic!("invalid response from screen");
+        }
+    }
+
+    pub fn getcolormode(&self) -> f32 {
+        if let Response::ColorMode(colormode) = self.do_command(ScreenCmd::GetColorMode) {
+            colormode
+        } else {
+            panic!("invalid response from screen");
+        }
+    }
+
+    pub fn getbgcolor(&self) -> TurtleColor {
+        if let Response::Color(color) = self.do_command(ScreenCmd::GetBgColor) {
+            color
+        } else {
+            panic!("invalid response from screen");
+        }
+    }
+
+    pub fn gettracer(&self) -> usize {
+        if let Response::Count(n) = self.do_command(ScreenCmd::GetTracer) {
+            n
+        } else {
+            panic!("invalid response from screen");
+        }
+    }
+
+    pub fn getdelay(&self) -> u64 {
+        if let Response::Count(delay) = self.do_command(ScreenCmd::GetDelay) {
+            delay as u64
+        } else {
+            panic!("invalid response from screen");
+        }
+    }
+
+    pub fn getscreensize(&self) -> [isize; 2] {
+        let response = self.do_command(ScreenCmd::GetScreenSize);
+        if let Response::ScreenSize(size) = response {
+            size
+        } else {
+            panic!("{}", format!("invalid response from screen: {response:?}"));
+        }
+    }
+
+    /*
+     * popup requests
+     */
+
+    pub fn textinput(&self, title: &str, prompt: &str) -> Option<String> {
+        match self.do_command(ScreenCmd::TextInput(title.into(), prompt.into())) {
+            Response::TextInput(string) => Some(string),
+            Response::Cancel => None,
+            bad_response => panic!("invalid response '{bad_response:?}' from screen"),
+        }
+    }
+
+    pub fn numinput(&self, title: &str, prompt: &str) -> Option<f32> {
+        match self.do_command(ScreenCmd::NumInput(title.into(), prompt.into())) {
+            Response::NumInput(num) => Some(num),
+            Response::Cancel => None,
+            bad_response => panic!("invalid response '{bad_response:?}' from screen"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        color_names::TurtleColor,
+        testing::{assert_at, lines, run},
+        TurtleMode,
+    };
+
+    #[test]
+    fn clearscreen_starts_again() {
+        let scene = run(|turtle| {
+            let screen = turtle.screen();
+            turtle.pencolor("red");
+            turtle.forward(50);
+            turtle.left(45);
+            screen.bgcolor("blue");
+            screen.mode("logo");
+            screen.colormode(255);
+            screen.tracer(0, 50);
+
+            screen.clearscreen();
+            assert_at(turtle, 0., 0.);
+            assert_eq!(turtle.heading(), 0.);
+            assert_eq!(turtle.getpencolor(), TurtleColor::from("black"));
+            assert_eq!(turtle.undobufferentries(), 0);
+            assert_eq!(screen.getbgcolor(), TurtleColor::from("white"));
+            assert_eq!(screen.getmode(), TurtleMode::Standard);
+            assert_eq!(screen.getcolormode(), 1.);
+            assert_eq!(screen.gettracer(), 1);
+            assert_eq!(screen.getdelay(), 10);
+        });
+
+        let drawn = lines(&scene.turtles[0].commands);
+        assert!(drawn.iter().all(|&(_, _, pen_down)| !pen_down), "{drawn:?}");
+    }
+
+    #[test]
+    fn resetscreen_keeps_the_screen() {
+        let scene = run(|turtle| {
+            let screen = turtle.screen();
+            let mut second = turtle.hatch();
+            turtle.forward(50);
+            second.left(90);
+            second.forward(20);
+            screen.bgcolor("blue");
+
+            screen.resetscreen();
+            for turtle in screen.turtles() {
+                assert_at(&turtle, 0., 0.);
+                assert_eq!(turtle.heading(), 0.);
+            }
+            assert_eq!(screen.getbgcolor(), TurtleColor::from("blue"));
+        });
+
+        for turtle in &scene.turtles {
+            let drawn = lines(&turtle.commands);
+            assert!(drawn.iter().all(|&(_, _, pen_down)| !pen_down), "{drawn:?}");
+        }
+    }
+
+    #[test]
+    fn turtles_in_order() {
+        run(|turtle| {
+            let mut second = turtle.hatch();
+            second.penup();
+            second.goto(10, 0);
+
+            let turtles = turtle.screen().turtles();
+            assert_eq!(turtles.len(), 2);
+            assert_at(&turtles[0], 0., 0.);
+            assert_at(&turtles[1], 10., 0.);
+        });
+    }
+
+    #[test]
+    fn screens_work_from_other_threads() {
+        run(|turtle| {
+            let screen = turtle.screen();
+            std::thread::spawn(move || {
+                screen.bgcolor("red");
+                let mut turtles = screen.turtles();
+                turtles[0].forward(30);
+            })
+            .join()
+            .unwrap();
+
+            assert_eq!(turtle.screen().getbgcolor(), TurtleColor::from("red"));
+            assert_at(turtle, 30., 0.);
+        });
+    }
+
+    #[test]
+    fn tracer_settings() {
+        run(|turtle| {
+            let screen = turtle.screen();
+            assert_eq!(screen.gettracer(), 1);
+            assert_eq!(screen.getdelay(), 10);
+
+            screen.tracer(3, None);
+            assert_eq!(screen.gettracer(), 3);
+            assert_eq!(screen.getdelay(), 10);
+            screen.tracer(0, 25);
+            assert_eq!(screen.gettracer(), 0);
+            assert_eq!(screen.getdelay(), 25);
+            screen.delay(0);
+            assert_eq!(screen.getdelay(), 0);
+        });
+    }
+
+    #[test]
+    fn update_shows_everything_drawn() {
+        let scene = run(|turtle| {
+            let screen = turtle.screen();
+            screen.tracer(0, None);
+            for _ in 0..36 {
+                turtle.forward(10);
+                turtle.left(10);
+            }
+            screen.update();
+            assert_at(turtle, 0., 0.);
+
+            // with tracing on, update() has nothing to wait for
+            screen.tracer(1, None);
+            turtle.forward(10);
+            screen.update();
+        });
+
+        assert_eq!(lines(&scene.turtles[0].commands).len(), 37);
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct TurtleSpeed(u8);
 
 impl Default for TurtleSpeed {
@@ -11,6 +11,21 @@ impl TurtleSpeed {
     pub fn get(&self) -> u8 {
         self.0
     }
+
+    /// How many seconds it takes to move `distance` pixels and turn `rotation`
+    /// degrees. Like python's turtle, each 10ms update moves 3 * 1.1^speed * speed
+    /// pixels or turns 3 * speed degrees.
+    pub(crate) fn duration(&self, distance: f32, rotation: f32) -> f32 {
+        if self.0 == 0 {
+            return 0.;
+        }
+
+        let speed = self.0 as f32;
+        let pixels_per_second = 3. * 1.1f32.powf(speed) * speed / 0.01;
+        let degrees_per_second = 3. * speed / 0.01;
+
+        distance.abs() / pixels_per_second + rotation.abs() / degrees_per_second
+    }
 }
 
 /* TODO: investigate using the num_traits crate
@@ -0,0 +1,280 @@
+use iced::{
+    widget::canvas::{path::Builder, Path},
+    Point,
+};
+use lyon_tessellation::path::Event;
+
+/// The shape drawn at the ends of a line
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+impl From<&str> for LineCap {
+    fn from(cap: &str) -> Self {
+        match cap {
+            "round" => Self::Round,
+            "square" | "projecting" => Self::Square,
+            _ => Self::Butt,
+        }
+    }
+}
+
+/// The shape drawn where two lines meet
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum LineJoin {
+    Miter,
+    #[default]
+    Round,
+    Bevel,
+}
+
+impl From<&str> for LineJoin {
+    fn from(join: &str) -> Self {
+        match join {
+            "miter" => Self::Miter,
+            "bevel" => Self::Bevel,
+            _ => Self::Round,
+        }
+    }
+}
+
+// How the ends and corners of lines are drawn
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub(crate) struct StrokeStyle {
+    pub(crate) cap: LineCap,
+    pub(crate) join: LineJoin,
+}
+
+// A dash pattern: dashes of `pattern[0]`, gaps of `pattern[1]` and so on,
+// starting `offset` into the pattern at the start of every subpath, as in SVG.
+// Paths are cut into their dashes before they're drawn, so that the iced
+// canvas and the PNG export agree on where the dashes fall.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct Dash {
+    pattern: Vec<f32>, // empty for a solid line
+    offset: f32,
+}
+
+impl Dash {
+    pub(crate) fn new(pattern: &[f32], offset: f32) -> Self {
+        // an odd number of lengths is repeated, as in SVG
+        let mut pattern = pattern.to_vec();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_within(..);
+        }
+        if pattern.iter().sum::<f32>() <= 0. {
+            pattern.clear();
+        }
+        Self { pattern, offset }
+    }
+
+    pub(crate) fn pattern(&self) -> &[f32] {
+        &self.pattern
+    }
+
+    pub(crate) fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    // The parts of `path` that fall on dashes
+    pub(crate) fn apply(&self, path: &Path) -> Path {
+        if self.pattern.is_empty() {
+            return path.clone();
+        }
+
+        Path::new(|b| {
+            let mut walk = DashWalk::new(&self.pattern, self.offset);
+            for event in path.raw() {
+                match event {
+                    Event::Begin { at } => {
+                        walk = DashWalk::new(&self.pattern, self.offset);
+                        walk.last = Point::new(at.x, at.y);
+                        b.move_to(walk.last);
+                    }
+                    Event::Line { to, .. }
+                    | Event::Quadratic { to, .. }
+                    | Event::Cubic { to, .. } => walk.line_to(b, Point::new(to.x, to.y)),
+                    Event::End { first, close, .. } => {
+                        if close {
+                            walk.line_to(b, Point::new(first.x, first.y));
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+// Where we are along a subpath, and in the dash pattern
+struct DashWalk<'a> {
+    pattern: &'a [f32],
+    index: usize, // even on a dash, odd on a gap
+    left: f32,    // of the current dash or gap
+    last: Point,
+}
+
+impl<'a> DashWalk<'a> {
+    fn new(pattern: &'a [f32], offset: f32) -> Self {
+        let total: f32 = pattern.iter().sum();
+        let mut head = offset.rem_euclid(total);
+        let mut index = 0;
+        while index < pattern.len() - 1 && head >= pattern[index] {
+            head -= pattern[index];
+            index += 1;
+        }
+
+        Self {
+            pattern,
+            index,
+            left: pattern[index] - head,
+            last: Point::ORIGIN,
+        }
+    }
+
+    fn line_to(&mut self, b: &mut Builder, to: Point) {
+        let from = self.last;
+        let length = from.distance(to);
+        let mut pos = 0.;
+
+        while length - pos > self.left {
+            pos += self.left;
+            let t = pos / length;
+            let point = Point::new(from.x + (to.x - from.x) * t, from.y + (to.y - from.y) * t);
+            if self.index.is_multiple_of(2) {
+                b.line_to(point);
+            } else {
+                b.move_to(point);
+            }
+            self.index = (self.index + 1) % self.pattern.len();
+            self.left = self.pattern[self.index];
+        }
+
+        self.left -= length - pos;
+        if self.index.is_multiple_of(2) {
+            b.line_to(to);
+        }
+        self.last = to;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the pieces of `path` that draw something, as lists of points
+    fn pieces(path: &Path) -> Vec<Vec<[f32; 2]>> {
+        let mut pieces: Vec<Vec<[f32; 2]>> = Vec::new();
+        for event in path.raw() {
+            match event {
+                Event::Begin { at } => pieces.push(vec![[at.x, at.y]]),
+                Event::Line { to, .. } => pieces.last_mut().unwrap().push([to.x, to.y]),
+                _ => {}
+            }
+        }
+        pieces.retain(|piece| piece.len() > 1);
+        pieces
+    }
+
+    fn line(points: &[[f32; 2]], close: bool) -> Path {
+        Path::new(|b| {
+            b.move_to(points[0].into());
+            for &point in &points[1..] {
+                b.line_to(point.into());
+            }
+            if close {
+                b.close();
+            }
+        })
+    }
+
+    fn length(pieces: &[Vec<[f32; 2]>]) -> f32 {
+        pieces
+            .iter()
+            .flat_map(|piece| piece.windows(2))
+            .map(|w| Point::from(w[0]).distance(Point::from(w[1])))
+            .sum()
+    }
+
+    #[test]
+    fn solid_lines_are_untouched() {
+        let path = line(&[[0., 0.], [6., 0.]], false);
+        assert_eq!(pieces(&Dash::new(&[], 0.).apply(&path)), pieces(&path));
+        assert_eq!(pieces(&Dash::default().apply(&path)), pieces(&path));
+    }
+
+    #[test]
+    fn zero_length_patterns_are_solid() {
+        assert!(Dash::new(&[0., 0.], 0.).pattern().is_empty());
+    }
+
+    #[test]
+    fn dashes_and_gaps() {
+        let path = line(&[[0., 0.], [6., 0.]], false);
+        assert_eq!(
+            pieces(&Dash::new(&[2., 1.], 0.).apply(&path)),
+            [vec![[0., 0.], [2., 0.]], vec![[3., 0.], [5., 0.]]]
+        );
+    }
+
+    #[test]
+    fn offset_starts_into_the_pattern() {
+        let path = line(&[[0., 0.], [6., 0.]], false);
+        assert_eq!(
+            pieces(&Dash::new(&[2., 1.], 1.).apply(&path)),
+            [
+                vec![[0., 0.], [1., 0.]],
+                vec![[2., 0.], [4., 0.]],
+                vec![[5., 0.], [6., 0.]]
+            ]
+        );
+    }
+
+    #[test]
+    fn odd_patterns_repeat() {
+        let dash = Dash::new(&[1.], 0.);
+        assert_eq!(dash.pattern(), [1., 1.]);
+
+        let path = line(&[[0., 0.], [4., 0.]], false);
+        assert_eq!(
+            pieces(&dash.apply(&path)),
+            [vec![[0., 0.], [1., 0.]], vec![[2., 0.], [3., 0.]]]
+        );
+    }
+
+    #[test]
+    fn dashes_turn_corners() {
+        let path = line(&[[0., 0.], [3., 0.], [3., 3.]], false);
+        assert_eq!(
+            pieces(&Dash::new(&[4., 1.], 0.).apply(&path)),
+            [vec![[0., 0.], [3., 0.], [3., 1.]], vec![[3., 2.], [3., 3.]]]
+        );
+    }
+
+    #[test]
+    fn closed_paths_dash_their_last_edge() {
+        let square = [[0., 0.], [4., 0.], [4., 4.], [0., 4.]];
+        let dashed = pieces(&Dash::new(&[1., 1.], 0.).apply(&line(&square, true)));
+        assert!((length(&dashed) - 8.).abs() < 1e-4);
+
+        let dashed = pieces(&Dash::new(&[1., 1.], 0.).apply(&line(&square, false)));
+        assert!((length(&dashed) - 6.).abs() < 1e-4);
+    }
+
+    #[test]
+    fn every_subpath_starts_the_pattern_again() {
+        let path = Path::new(|b| {
+            b.move_to(Point::new(0., 0.));
+            b.line_to(Point::new(3., 0.));
+            b.move_to(Point::new(0., 1.));
+            b.line_to(Point::new(3., 1.));
+        });
+        assert_eq!(
+            pieces(&Dash::new(&[2., 2.], 0.).apply(&path)),
+            [vec![[0., 0.], [2., 0.]], vec![[0., 1.], [2., 1.]]]
+        );
+    }
+}
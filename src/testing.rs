@@ -0,0 +1,43 @@
+// Fixtures shared by the tests: turtle programs run headless, and what they
+// drew is read back out of the scene
+
+use crate::{generate::DrawCommand, Scene, Turtle, TurtleArgs};
+
+pub(crate) fn run<F: FnOnce(&mut Turtle) + Send + 'static>(func: F) -> Scene {
+    TurtleArgs::default().with_size(200, 200).run_headless(func)
+}
+
+// the start, end and pen state of every line
+pub(crate) fn lines(commands: &[DrawCommand]) -> Vec<([f32; 2], [f32; 2], bool)> {
+    commands
+        .iter()
+        .filter_map(|cmd| match cmd {
+            DrawCommand::Line(line) => Some((
+                [line.begin.x, line.begin.y],
+                [line.end.x, line.end.y],
+                line.pen_down,
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+pub(crate) fn line_ends(commands: &[DrawCommand]) -> Vec<[f32; 2]> {
+    lines(commands).into_iter().map(|(_, end, _)| end).collect()
+}
+
+pub(crate) fn assert_at(turtle: &Turtle, x: f32, y: f32) {
+    let pos = turtle.pos();
+    assert!(
+        (pos.x - x).abs() < 1e-3 && (pos.y - y).abs() < 1e-3,
+        "{pos:?} isn't ({x}, {y})"
+    );
+}
+
+// the RGBA color of the pixel at canvas position (x, y), counted from the
+// center of the window with y pointing down
+pub(crate) fn pixel(scene: &Scene, x: isize, y: isize) -> [u8; 4] {
+    let [width, height] = scene.size;
+    let index = ((y + height / 2) * width + x + width / 2) as usize * 4;
+    scene.to_rgba()[index..index + 4].try_into().unwrap()
+}
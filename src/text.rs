@@ -0,0 +1,174 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use iced::{
+    advanced::graphics::{
+        self,
+        text::cosmic_text::{AttrsList, BufferLine, Wrap},
+    },
+    alignment::{Horizontal, Vertical},
+    font::{Family, Style, Weight},
+    widget::{
+        canvas::Text,
+        text::{LineHeight, Shaping},
+    },
+    Color, Font, Pixels, Point,
+};
+
+/// Where the text goes relative to the turtle, as in python's `align=`
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl From<&str> for TextAlign {
+    fn from(align: &str) -> Self {
+        match align {
+            "center" => Self::Center,
+            "right" => Self::Right,
+            _ => Self::Left,
+        }
+    }
+}
+
+/// A font description, as in python's `font=("Arial", 8, "normal")`. The style
+/// may contain any of "bold", "italic" and "underline".
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurtleFont {
+    pub family: String,
+    pub size: f32,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl Default for TurtleFont {
+    fn default() -> Self {
+        Self {
+            family: "Arial".into(),
+            size: 8.,
+            bold: false,
+            italic: false,
+            underline: false,
+        }
+    }
+}
+
+impl<N: Into<f64>> From<(&str, N, &str)> for TurtleFont {
+    fn from((family, size, style): (&str, N, &str)) -> Self {
+        Self {
+            family: family.into(),
+            size: size.into() as f32,
+            bold: style.contains("bold"),
+            italic: style.contains("italic"),
+            underline: style.contains("underline"),
+        }
+    }
+}
+
+impl<N: Into<f64>> From<(&str, N)> for TurtleFont {
+    fn from((family, size): (&str, N)) -> Self {
+        (family, size, "normal").into()
+    }
+}
+
+impl TurtleFont {
+    fn iced_font(&self) -> Font {
+        let family = match self.family.to_lowercase().as_str() {
+            "arial" | "helvetica" | "sans" | "sans-serif" | "sansserif" => Family::SansSerif,
+            "courier" | "courier new" | "mono" | "monospace" => Family::Monospace,
+            "times" | "times new roman" | "serif" => Family::Serif,
+            "cursive" => Family::Cursive,
+            "fantasy" => Family::Fantasy,
+            _ => Family::Name(intern(&self.family)),
+        };
+
+        Font {
+            family,
+            weight: if self.bold {
+                Weight::Bold
+            } else {
+                Weight::Normal
+            },
+            style: if self.italic {
+                Style::Italic
+            } else {
+                Style::Normal
+            },
+            ..Font::DEFAULT
+        }
+    }
+
+    // The text is anchored at its bottom edge, like python's tkinter canvas does it
+    pub(crate) fn text(&self, content: &str, pos: Point, color: Color, align: TextAlign) -> Text {
+        Text {
+            content: content.into(),
+            position: pos,
+            color,
+            size: Pixels(self.size),
+            line_height: LineHeight::default(),
+            font: self.iced_font(),
+            horizontal_alignment: match align {
+                TextAlign::Left => Horizontal::Left,
+                TextAlign::Center => Horizontal::Center,
+                TextAlign::Right => Horizontal::Right,
+            },
+            vertical_alignment: Vertical::Bottom,
+            shaping: Shaping::Advanced,
+        }
+    }
+
+    /// How wide `content` is when drawn in this font
+    pub(crate) fn width(&self, content: &str) -> f32 {
+        let mut font_system = graphics::text::font_system().write().expect("font system");
+
+        let mut line = BufferLine::new(
+            content,
+            AttrsList::new(graphics::text::to_attributes(self.iced_font())),
+            graphics::text::to_shaping(Shaping::Advanced),
+        );
+        line.layout(font_system.raw(), self.size, f32::MAX, Wrap::None)
+            .iter()
+            .fold(0., |width, layout| width.max(layout.w))
+    }
+
+    /// Where to draw the underline for `content`, and how thick: (start, end, width)
+    pub(crate) fn underline(
+        &self,
+        content: &str,
+        pos: Point,
+        align: TextAlign,
+    ) -> (Point, Point, f32) {
+        let width = self.width(content);
+        let left = match align {
+            TextAlign::Left => pos.x,
+            TextAlign::Center => pos.x - width / 2.,
+            TextAlign::Right => pos.x - width,
+        };
+        let y = pos.y - self.size * 0.2;
+
+        (
+            Point::new(left, y),
+            Point::new(left + width, y),
+            (self.size / 15.).max(1.),
+        )
+    }
+}
+
+// iced wants a `&'static str` for a font name, so keep one copy of each around
+fn intern(name: &str) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashMap<String, &'static str>>> = OnceLock::new();
+
+    let mut names = NAMES
+        .get_or_init(Default::default)
+        .lock()
+        .expect("font name table");
+    names
+        .entry(name.to_string())
+        .or_insert_with(|| Box::leak(name.to_string().into_boxed_str()))
+}
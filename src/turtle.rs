@@ -1,30 +1,48 @@
 pub(crate) mod types;
+mod undo;
 
 use std::{
-    cell::RefCell,
-    collections::{HashMap, VecDeque},
-    f32::consts::PI,
-    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    cell::Cell,
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc,
+    },
+    thread::JoinHandle,
+    time::{Duration, Instant},
 };
 
 use crate::{
-    gui::{events::TurtleEvent, iced_gui::IcedGuiFramework, Progression, StampCount},
+    gui::{
+        events::TurtleEvent, headless::HeadlessGuiFramework, iced_gui::IcedGuiFramework,
+        Progression,
+    },
     turtle::types::TurtleID,
 };
 
-use lyon_tessellation::geom::euclid::default::Transform2D;
+use lyon_tessellation::geom::{euclid::default::Transform2D, Angle};
 use types::TurtleThread;
+use undo::{UndoBuffer, UndoEntry};
 
 use crate::{
     color_names::TurtleColor,
     command::{
-        Command, DataCmd, DrawRequest, InputCmd, InstantaneousDrawCmd, ScreenCmd, TimedDrawCmd,
+        Command, DataCmd, DrawRequest, InputCmd, InstantaneousDrawCmd, MotionCmd, ScreenCmd,
+        TimedDrawCmd, TurtleCmd,
     },
     comms::{Request, Response},
+    coords::{scale_angle, Coordinates},
     generate::{CurrentTurtleState, DrawCommand, TurtlePosition},
-    polygon::{generate_default_shapes, TurtlePolygon, TurtleShape},
+    input::{
+        Callback, HandlerHandle, KeyHandler, KeyModifiers, MouseButton, MouseHandler,
+        ScreenKeyHandler, ScreenMouseHandler, TimerHandle, TimerHandler, TurtleKey,
+    },
+    polygon::{generate_default_shapes, FillRule, TurtlePolygon, TurtleShape},
+    scene::Scene,
+    screen::Screen,
     speed::TurtleSpeed,
-    ScreenPosition, TurtleShapeName,
+    PenState, ScreenPosition, StampID, TurtleShapeName,
 };
 
 #[derive(Debug)]
@@ -61,6 +79,22 @@ impl TurtleArgs {
     pub fn run<F: FnOnce(&mut Turtle) + Send + 'static>(&self, func: F) {
         Turtle::run(self, func)
     }
+
+    pub fn run_headless<F: FnOnce(&mut Turtle) + Send + 'static>(&self, func: F) -> Scene {
+        Turtle::run_headless(self, func)
+    }
+
+    fn flags<F: FnOnce(&mut Turtle) + Send + 'static>(&self, func: F) -> TurtleFlags {
+        let (issue_command, receive_command) = mpsc::channel();
+
+        TurtleFlags {
+            start_func: Some(Box::new(func)),
+            issue_command: Some(issue_command),
+            receive_command: Some(receive_command),
+            title: self.title.clone(),
+            size: [self.size[0] as f32, self.size[1] as f32],
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -69,7 +103,8 @@ pub struct Turtle {
     command_complete: Receiver<Response>,
     turtle: TurtleID,
     thread: TurtleThread,
-    tracer: RefCell<bool>,
+    tracing: Arc<AtomicBool>, // shared with the screen; commands aren't waited for when off
+    unanswered: Cell<usize>,  // commands sent without waiting for their response
 }
 
 impl Turtle {
@@ -79,18 +114,7 @@ impl Turtle {
     }
 
     pub fn run<F: FnOnce(&mut Turtle) + Send + 'static>(args: &TurtleArgs, func: F) {
-        let xsize = args.size[0] as f32;
-        let ysize = args.size[1] as f32;
-
-        let (issue_command, receive_command) = mpsc::channel();
-
-        let flags = TurtleFlags {
-            start_func: Some(Box::new(func)),
-            issue_command: Some(issue_command),
-            receive_command: Some(receive_command),
-            title: args.title.clone(),
-            size: [xsize, ysize],
-        };
+        let flags = args.flags(func);
 
         // #[cfg(an option to specify the "iced" crate for the gui)]
         IcedGuiFramework::start(flags);
@@ -100,18 +124,29 @@ impl Turtle {
         // OtherGui::start(flags);
     }
 
+    /// Run the turtle program without opening a window. Returns once `func` has
+    /// returned and everything it asked for has been drawn.
+    pub fn run_headless<F: FnOnce(&mut Turtle) + Send + 'static>(
+        args: &TurtleArgs,
+        func: F,
+    ) -> Scene {
+        HeadlessGuiFramework::start(args.flags(func))
+    }
+
     pub(crate) fn init(
         issue_command: Sender<Request>,
         command_complete: Receiver<Response>,
         turtle: TurtleID,
         thread: TurtleThread,
+        tracing: Arc<AtomicBool>,
     ) -> Self {
         Self {
             issue_command,
             command_complete,
             turtle,
             thread,
-            tracer: true.into(),
+            tracing,
+            unanswered: Cell::new(0),
         }
     }
 
@@ -119,8 +154,8 @@ impl Turtle {
         let _ = self.do_command(Command::Draw(cmd));
     }
 
-    pub(crate) fn do_screen(&mut self, cmd: ScreenCmd) {
-        let _ = self.do_command(Command::Screen(cmd));
+    pub(crate) fn do_turtle(&mut self, cmd: TurtleCmd) {
+        let _ = self.do_command(Command::Turtle(cmd));
     }
 
     pub(crate) fn do_input(&self, cmd: InputCmd) {
@@ -140,8 +175,13 @@ impl Turtle {
         }
     }
 
+    /// The screen this turtle draws on
+    pub fn screen(&self) -> Screen {
+        Screen::new(self.issue_command.clone())
+    }
+
     fn req(&self, cmd: Command) -> Request {
-        Request {
+        Request::Turtle {
             turtle: self.turtle,
             thread: self.thread,
             cmd,
@@ -149,51 +189,34 @@ impl Turtle {
     }
 
     fn do_command(&self, cmd: Command) -> Response {
-        let is_data_cmd = matches!(cmd, Command::Data(_));
-        let tracer_was_off = !*self.tracer.borrow();
-        if let Command::Draw(DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Tracer(t))) = &cmd
-        {
-            *self.tracer.borrow_mut() = *t;
-        }
+        let needs_answer = matches!(cmd, Command::Data(_) | Command::Hatch);
 
         if self.issue_command.send(self.req(cmd)).is_ok() {
-            if *self.tracer.borrow() {
-                if tracer_was_off {
-                    // need to consume all but the last response
-                    let saved_response = self.command_complete.recv();
-                    if let Ok(response) = saved_response {
-                        let mut return_value = response;
-                        loop {
-                            match self.command_complete.try_recv() {
-                                Ok(response) => return_value = response,
-                                Err(TryRecvError::Empty) => return return_value,
-                                Err(TryRecvError::Disconnected) => panic!("lost main thread"),
-                            }
-                        }
+            if needs_answer {
+                // data is answered straight away, possibly ahead of the
+                // commands that weren't waited for
+                while let Ok(response) = self.command_complete.recv() {
+                    if matches!(response, Response::Done) && self.unanswered.get() > 0 {
+                        self.unanswered.set(self.unanswered.get() - 1);
+                    } else {
+                        return response;
                     }
-                } else if let Ok(result) = self.command_complete.recv() {
-                    return result;
                 }
-            } else if is_data_cmd {
-                loop {
-                    if let Ok(result) = self.command_complete.recv() {
-                        if matches!(result, Response::Done) {
-                            continue;
-                        } else {
-                            return result;
-                        }
+            } else if self.tracing.load(Ordering::Acquire) {
+                // catch up on the commands that weren't waited for, then wait
+                // for this one
+                while let Ok(response) = self.command_complete.recv() {
+                    if self.unanswered.get() == 0 {
+                        return response;
                     }
+                    self.unanswered.set(self.unanswered.get() - 1);
                 }
             } else {
+                self.unanswered.set(self.unanswered.get() + 1);
                 loop {
                     match self.command_complete.try_recv() {
-                        Ok(response) => {
-                            if matches!(response, Response::Done) {
-                                continue;
-                            } else {
-                                panic!("Received data response: {response:?}");
-                            }
-                        }
+                        Ok(Response::Done) => self.unanswered.set(self.unanswered.get() - 1),
+                        Ok(response) => panic!("Received data response: {response:?}"),
                         Err(TryRecvError::Empty) => return Response::Done,
                         Err(TryRecvError::Disconnected) => panic!("lost main thread"),
                     }
@@ -208,23 +231,51 @@ impl Turtle {
     }
 }
 
-#[derive(Default)]
+// let the turtle task forget about this handle's thread
+impl Drop for Turtle {
+    fn drop(&mut self) {
+        let _ = self
+            .issue_command
+            .send(Request::shut_down(self.turtle, self.thread));
+    }
+}
+
+#[derive(Default, Clone)]
 struct PolygonBuilder {
-    last_point: Option<ScreenPosition<isize>>,
+    last_point: Option<ScreenPosition<f32>>,
     verticies: Vec<[f32; 2]>,
+    subpaths: Vec<Vec<[f32; 2]>>, // finished before the pen was lifted
 }
 
 impl PolygonBuilder {
-    fn start(&mut self, pos: ScreenPosition<isize>) {
+    fn start(&mut self, pos: ScreenPosition<f32>) {
         self.last_point = Some(pos);
-        self.verticies = vec![[pos.x as f32, pos.y as f32]];
+        self.verticies = vec![[pos.x, pos.y]];
+        self.subpaths.clear();
     }
 
-    fn update(&mut self, pos: ScreenPosition<isize>) {
+    // begin a new subpath at `pos`, leaving the current one behind
+    fn jump(&mut self, pos: ScreenPosition<f32>) {
+        if self.last_point.is_some() {
+            let subpath = std::mem::replace(&mut self.verticies, vec![[pos.x, pos.y]]);
+            if subpath.len() > 1 {
+                self.subpaths.push(subpath);
+            }
+            self.last_point = Some(pos);
+        }
+    }
+
+    fn take_subpaths(&mut self) -> Vec<Vec<[f32; 2]>> {
+        self.last_point = None;
+        let mut subpaths = std::mem::take(&mut self.subpaths);
+        subpaths.push(std::mem::take(&mut self.verticies));
+        subpaths
+    }
+
+    fn update(&mut self, pos: ScreenPosition<f32>) {
         if let Some(p) = self.last_point {
             if p != pos {
-                let new_point = [pos.x as f32, pos.y as f32];
-                self.verticies.push(new_point);
+                self.verticies.push([pos.x, pos.y]);
                 self.last_point = Some(pos);
             }
         }
@@ -235,6 +286,56 @@ impl PolygonBuilder {
             self.verticies.push(self.verticies[0]);
         }
     }
+
+    // Lines only add points and start new subpaths, so a few lengths are enough
+    // to go back to. Starting or finishing the polygon throws points away, so
+    // the commands that do that keep a copy instead.
+    fn mark(&self, restarts: bool) -> PolygonMark {
+        if restarts {
+            PolygonMark::Copy(self.clone())
+        } else {
+            PolygonMark::Length {
+                last_point: self.last_point,
+                first: self.verticies.first().copied(),
+                verticies: self.verticies.len(),
+                subpaths: self.subpaths.len(),
+            }
+        }
+    }
+
+    fn restore(&mut self, mark: PolygonMark) {
+        match mark {
+            PolygonMark::Copy(builder) => *self = builder,
+            PolygonMark::Length {
+                last_point,
+                first,
+                verticies,
+                subpaths,
+            } => {
+                // carry on with the subpaths that jumps left behind
+                while self.subpaths.len() > subpaths {
+                    self.verticies = self.subpaths.pop().unwrap();
+                }
+                self.verticies.truncate(verticies);
+                // a jump from a lone point drops it rather than leaving it behind
+                if let (Some(first), Some(start)) = (first, self.verticies.first_mut()) {
+                    *start = first;
+                }
+                self.last_point = last_point;
+            }
+        }
+    }
+}
+
+// how far a `PolygonBuilder` had got before a command, so it can be undone
+enum PolygonMark {
+    Length {
+        last_point: Option<ScreenPosition<f32>>,
+        first: Option<[f32; 2]>,
+        verticies: usize,
+        subpaths: usize,
+    },
+    Copy(PolygonBuilder),
 }
 
 #[derive(Default)]
@@ -243,43 +344,76 @@ pub(crate) struct TurtleInternalData {
 
     current_command: Option<DrawRequest>, // what we're drawing now
     current_shape: CurrentTurtleState,
-    current_stamp: usize,
+    current_stamp: StampID,
+    next_stamp: StampID,
+    stamps: BTreeMap<StampID, usize>, // stamp ids, oldest first, and where they were drawn
+    shape: TurtleShape,               // before it's stretched, sheared and tilted
+    fill_rule: FillRule,              // for the fill in progress
 
     current_turtle: TurtleID,
     current_thread: TurtleThread,
 
     percent: f32,
     progression: Progression,
+    motion: (f32, f32), // distance and rotation of the current command, to time it
     insert_fill: Option<usize>,
+    undo_buffer: UndoBuffer,
     responder: HashMap<TurtleThread, Sender<Response>>,
-    onkeypress: HashMap<char, fn(&mut Turtle, char)>,
-    onkeyrelease: HashMap<char, fn(&mut Turtle, char)>,
-    onmousepress: Option<fn(&mut Turtle, x: f32, y: f32)>,
-    onmouserelease: Option<fn(&mut Turtle, x: f32, y: f32)>,
-    onmousedrag: Option<fn(&mut Turtle, x: f32, y: f32)>,
+    onmousepress: Bindings<MouseButton, Callback<MouseHandler>>,
+    onmouserelease: Bindings<MouseButton, Callback<MouseHandler>>,
+    onmousedrag: Bindings<MouseButton, Callback<MouseHandler>>,
+    grabbed: bool, // the last mouse press landed on this turtle
     drawing_done: bool,
-    tracer: bool,
-    respond_immediately: bool,
     speed: TurtleSpeed,
     next_thread: TurtleThread,
 
     fill_poly: PolygonBuilder,
     shape_poly: PolygonBuilder,
+}
+
+// event handlers, along with the handle that can unbind them
+type Bindings<K, H> = HashMap<K, (H, HandlerHandle)>;
 
-    pending_keys: bool,
+// the handler bound to `key`, unless it has been removed since
+fn bound<K: Eq + std::hash::Hash, H: Clone>(bindings: &Bindings<K, H>, key: &K) -> Option<H> {
+    bindings
+        .get(key)
+        .filter(|(_, handle)| !handle.is_removed())
+        .map(|(func, _)| func.clone())
 }
 
-impl TurtleInternalData {
-    fn pending_key_event(&mut self) -> bool {
-        if self.pending_keys {
-            true
-        } else {
-            self.pending_keys = true;
-            false
+fn rebind<K: Eq + std::hash::Hash, H>(
+    bindings: &mut Bindings<K, H>,
+    key: K,
+    func: Option<H>,
+    handle: HandlerHandle,
+) {
+    if let Some(func) = func {
+        bindings.insert(key, (func, handle));
+    } else {
+        bindings.remove(&key);
+    }
+}
+
+// A handler for a screen-wide event, which is given either the turtle that
+// bound it or the screen
+enum Binding<T, S> {
+    Turtle(TurtleID, Callback<T>),
+    Screen(Callback<S>),
+}
+
+impl<T, S> Clone for Binding<T, S> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Turtle(turtle, func) => Self::Turtle(*turtle, func.clone()),
+            Self::Screen(func) => Self::Screen(func.clone()),
         }
     }
 }
 
+type KeyBinding = Binding<KeyHandler, ScreenKeyHandler>;
+type ClickBinding = Binding<MouseHandler, ScreenMouseHandler>;
+
 use crate::gui::TurtleGui;
 #[derive(Default)]
 pub(crate) struct TurtleData {
@@ -291,7 +425,6 @@ impl TurtleData {
         Self {
             data: TurtleInternalData {
                 percent: 2.,
-                tracer: true,
                 ..TurtleInternalData::default()
             },
         }
@@ -302,16 +435,91 @@ impl TurtleData {
         turtle: TurtleID,
         thread: TurtleThread,
         issue_command: Sender<Request>,
+        tracing: Arc<AtomicBool>,
     ) -> Turtle {
         let (finished, command_complete) = mpsc::channel();
         self.data.responder.insert(thread, finished);
 
-        Turtle::init(issue_command, command_complete, turtle, thread)
+        Turtle::init(issue_command, command_complete, turtle, thread, tracing)
     }
 
     fn convert_command<G: TurtleGui>(&mut self, cmd: &DrawRequest, gui: &mut G) {
+        self.data.motion = (0., 0.);
+
+        if let DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Pen(state)) = cmd {
+            self.set_pen(state, gui);
+            return;
+        }
+
+        if let DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::UndoBuffer(limit)) = cmd {
+            self.data.undo_buffer.resize(*limit);
+            return;
+        }
+
+        match cmd {
+            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Clear) => {
+                self.clear(self.data.current_turtle, gui);
+                return;
+            }
+            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Reset) => {
+                self.reset(self.data.current_turtle, gui);
+                return;
+            }
+            _ => {}
+        }
+
+        if cmd.changes_shape() {
+            self.data.current_shape.apply(cmd);
+            self.update_shape(self.data.current_turtle, gui);
+            return;
+        }
+
+        // as in python, teleporting without `fill_gap` fills what's been drawn
+        // so far and starts again from the new position
+        if let DrawRequest::TimedDraw(TimedDrawCmd::Motion(MotionCmd::Teleport(x, y, false))) = cmd
+        {
+            if self.data.fill_poly.last_point.is_some() {
+                let teleport = MotionCmd::Teleport(*x, *y, true);
+                for cmd in [
+                    DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::EndFill),
+                    DrawRequest::TimedDraw(TimedDrawCmd::Motion(teleport)),
+                    DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::BeginFill),
+                ] {
+                    self.convert_command(&cmd, gui);
+                }
+                return;
+            }
+        }
+        // writing and moving past the text are undone together, as in python
+        if let DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Write(
+            text,
+            align,
+            font,
+            Some(dx),
+        )) = cmd
+        {
+            for cmd in [
+                DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Write(
+                    text.clone(),
+                    *align,
+                    font.clone(),
+                    None,
+                )),
+                DrawRequest::TimedDraw(TimedDrawCmd::Motion(MotionCmd::Shift(*dx))),
+            ] {
+                self.convert_command(&cmd, gui);
+            }
+            return;
+        }
+
+        let fill_gap = matches!(
+            cmd,
+            DrawRequest::TimedDraw(TimedDrawCmd::Motion(MotionCmd::Teleport(_, _, true)))
+        );
+
         if let Some(command) = self.data.current_shape.apply(cmd) {
             let tid = self.data.current_turtle;
+            self.data.motion = command.motion();
 
             if matches!(command, DrawCommand::Filler) {
                 self.data.insert_fill = Some(gui.get_position(tid))
@@ -319,28 +527,41 @@ impl TurtleData {
 
             match &command {
                 DrawCommand::Line(lineinfo) => {
-                    // TODO: when "teleporting" instead of goto/setpos, we're only supposed
-                    // to continue the current polygon if fill_gap=True (see python docs)
-                    self.data.fill_poly.update(lineinfo.end);
+                    // moving with the pen up starts a new part of the fill
+                    if lineinfo.pen_down || fill_gap {
+                        self.data.fill_poly.update(lineinfo.end);
+                    } else {
+                        self.data.fill_poly.jump(lineinfo.end);
+                    }
                     self.data.shape_poly.update(lineinfo.end);
                     gui.append_command(tid, command);
                 }
                 DrawCommand::Circle(circle) => {
                     for c in circle {
-                        self.data.fill_poly.update([c.x, c.y].into());
                         self.data.shape_poly.update([c.x, c.y].into());
                     }
+                    if circle[0].pen_down {
+                        for c in circle {
+                            self.data.fill_poly.update([c.x, c.y].into());
+                        }
+                    } else if let Some(c) = circle.last() {
+                        self.data.fill_poly.jump([c.x, c.y].into());
+                    }
                     gui.append_command(tid, command);
                 }
                 DrawCommand::DrawPolygon(_) => {
                     panic!("oops");
                 }
                 DrawCommand::StampTurtle => {
-                    self.data.current_stamp = gui.stamp(
+                    let index = gui.stamp(
                         tid,
                         self.data.current_shape.pos(),
-                        self.data.current_shape.angle,
+                        self.data.current_shape.canvas_angle(),
                     );
+                    // ids keep counting up, so an old id never finds a new stamp
+                    self.data.current_stamp = self.data.next_stamp;
+                    self.data.next_stamp += 1;
+                    self.data.stamps.insert(self.data.current_stamp, index);
                 }
                 DrawCommand::BeginPoly => {
                     let pos_copy = self.data.current_shape.pos();
@@ -352,13 +573,22 @@ impl TurtleData {
                 DrawCommand::BeginFill => {
                     let pos_copy = self.data.current_shape.pos();
                     self.data.fill_poly.start(pos_copy);
+                    self.data.fill_rule = self.data.current_shape.fill_rule();
                     self.data.insert_fill = Some(gui.get_position(tid));
                     gui.append_command(tid, DrawCommand::Filler);
                 }
                 DrawCommand::EndFill => {
                     if !self.data.fill_poly.verticies.is_empty() {
-                        let polygon = TurtlePolygon::new(&self.data.fill_poly.verticies);
-                        self.data.fill_poly.last_point = None;
+                        let polygon = TurtlePolygon::from_subpaths(
+                            self.data.fill_poly.take_subpaths(),
+                            self.data.fill_rule,
+                        );
+                        // gradients are worked out from the finished shape
+                        let state = &self.data.current_shape;
+                        let paint = state
+                            .fill_style()
+                            .and_then(|style| style.paint(&polygon, &state.coords));
+                        let polygon = polygon.with_paint(paint);
                         if let Some(index) = self.data.insert_fill.take() {
                             gui.fill_polygon(tid, DrawCommand::DrawPolygon(polygon), index);
                         }
@@ -371,6 +601,179 @@ impl TurtleData {
         }
     }
 
+    // everything in the pen state changes between one frame and the next
+    fn set_pen<G: TurtleGui>(&mut self, state: &PenState, gui: &mut G) {
+        let pen = if state.pendown {
+            InstantaneousDrawCmd::PenDown
+        } else {
+            InstantaneousDrawCmd::PenUp
+        };
+
+        for cmd in [
+            pen,
+            InstantaneousDrawCmd::PenColor(state.pencolor),
+            InstantaneousDrawCmd::FillColor(state.fillcolor),
+            InstantaneousDrawCmd::PenWidth(state.pensize),
+        ] {
+            self.convert_command(&DrawRequest::InstantaneousDraw(cmd), gui);
+        }
+
+        // the shape only needs to be handed to the gui once
+        let (wid, len) = state.stretchfactor;
+        let shape_cmds = [
+            InstantaneousDrawCmd::ShapeSize(wid, len),
+            InstantaneousDrawCmd::ShearFactor(state.shearfactor),
+            InstantaneousDrawCmd::TiltAngle(state.tilt),
+            InstantaneousDrawCmd::ShapeOutline(state.outline),
+        ];
+        for cmd in shape_cmds {
+            self.data
+                .current_shape
+                .apply(&DrawRequest::InstantaneousDraw(cmd));
+        }
+        self.update_shape(self.data.current_turtle, gui);
+        self.data.speed = state.speed;
+        gui.set_visible(self.data.current_turtle, state.shown);
+    }
+
+    // python's `clear()`: rub out the turtle's drawing, stamps and undo
+    // history, and carry on from the same place with the same pen
+    fn clear<G: TurtleGui>(&mut self, turtle: TurtleID, gui: &mut G) {
+        gui.clear(turtle);
+        for cmd in self.data.current_shape.redraw() {
+            gui.append_command(turtle, cmd);
+        }
+
+        // as in python, a fill in progress is dropped along with the drawing
+        self.data.fill_poly = PolygonBuilder::default();
+        self.data.insert_fill = None;
+        self.data.undo_buffer.clear();
+        self.data.stamps.clear();
+    }
+
+    // python's `clearstamps(n)`: the first `n` stamps, the last `-n`, or all
+    // of them for 0
+    fn clear_stamps<G: TurtleGui>(&mut self, turtle: TurtleID, count: isize, gui: &mut G) {
+        let ids: Vec<StampID> = if count < 0 {
+            let count = count.unsigned_abs();
+            self.data.stamps.keys().rev().take(count).copied().collect()
+        } else if count == 0 {
+            self.data.stamps.keys().copied().collect()
+        } else {
+            let count = count as usize;
+            self.data.stamps.keys().take(count).copied().collect()
+        };
+
+        for id in ids {
+            if let Some(index) = self.data.stamps.remove(&id) {
+                gui.clear_stamp(turtle, index);
+            }
+        }
+    }
+
+    // python's `reset()`: clear, and put the turtle back home with the
+    // default pen, speed and shape transform. The shape itself is kept.
+    fn reset<G: TurtleGui>(&mut self, turtle: TurtleID, gui: &mut G) {
+        self.data.current_shape.reset();
+        self.data.speed = TurtleSpeed::default();
+        gui.set_visible(turtle, true);
+        self.update_shape(turtle, gui);
+        self.clear(turtle, gui);
+    }
+
+    // remember how things were before `cmd`, so it can be undone
+    fn save_undo<G: TurtleGui>(&mut self, cmd: &DrawRequest, gui: &G) {
+        if !cmd.is_undoable() || !self.data.undo_buffer.is_enabled() {
+            return;
+        }
+
+        let turtle = self.data.current_turtle;
+        let pen = matches!(
+            cmd,
+            DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Pen(_))
+        )
+        .then(|| (self.data.speed, gui.is_visible(turtle)));
+        self.data.undo_buffer.push(UndoEntry {
+            cmd: cmd.clone(),
+            state: self.data.current_shape.clone(),
+            fill_poly: self.data.fill_poly.mark(cmd.restarts_fill()),
+            shape_poly: self.data.shape_poly.mark(cmd.restarts_poly()),
+            fill_rule: self.data.fill_rule,
+            insert_fill: self.data.insert_fill,
+            cmds: gui.get_position(turtle),
+            pen,
+        });
+    }
+
+    // take away whatever the last command drew, and put the turtle back
+    fn undo<G: TurtleGui>(&mut self, gui: &mut G) {
+        let Some(entry) = self.data.undo_buffer.pop() else {
+            return;
+        };
+
+        let turtle = self.data.current_turtle;
+        while gui.get_position(turtle) > entry.cmds {
+            gui.pop(turtle);
+        }
+        self.data.stamps.retain(|_, index| *index < entry.cmds);
+
+        // the coordinates belong to the screen, so they stay as they are
+        let coords = self.data.current_shape.coords;
+        self.data.current_shape = entry.state;
+        self.data.current_shape.coords = coords;
+        self.data.fill_poly.restore(entry.fill_poly);
+        self.data.shape_poly.restore(entry.shape_poly);
+        self.data.fill_rule = entry.fill_rule;
+        self.data.insert_fill = entry.insert_fill;
+        if let Some((speed, shown)) = entry.pen {
+            self.data.speed = speed;
+            gui.set_visible(turtle, shown);
+        }
+        self.update_shape(turtle, gui);
+    }
+
+    // the last command's drawing, if there's anything to animate backwards
+    fn undo_motion<G: TurtleGui>(&self, gui: &G) -> Option<(f32, f32)> {
+        let turtle = self.data.current_turtle;
+        let entry = self.data.undo_buffer.last()?;
+        if gui.get_position(turtle) <= entry.cmds {
+            return None;
+        }
+        gui.last_command(turtle)
+            .map(DrawCommand::motion)
+            .filter(|&motion| motion != (0., 0.))
+    }
+
+    fn pen<G: TurtleGui>(&self, turtle: TurtleID, gui: &G) -> PenState {
+        let shape = &self.data.current_shape;
+        let transform = shape.shape_transform();
+        let [wid, len] = transform.stretch();
+        PenState {
+            pendown: shape.pen_down(),
+            pencolor: shape.pen_color(),
+            fillcolor: shape.fill_color(),
+            pensize: shape.pen_width(),
+            speed: self.data.speed,
+            shown: gui.is_visible(turtle),
+            stretchfactor: (wid, len),
+            outline: transform.outline(),
+            shearfactor: transform.shear(),
+            tilt: shape.tiltangle(),
+        }
+    }
+
+    // give the gui the shape as it's drawn, after any stretch, shear and tilt
+    fn update_shape<G: TurtleGui>(&self, turtle: TurtleID, gui: &mut G) {
+        let transform = self.data.current_shape.shape_transform();
+        gui.set_shape(
+            turtle,
+            TurtleShape {
+                name: self.data.shape.name.clone(),
+                shape: self.data.shape.shape.transformed(&transform),
+            },
+        );
+    }
+
     fn is_instantaneous(&self) -> bool {
         if let Some(cmd) = self.data.current_command.as_ref() {
             matches!(cmd, DrawRequest::InstantaneousDraw(_))
@@ -379,10 +782,23 @@ impl TurtleData {
         }
     }
 
-    fn time_passes<G: TurtleGui>(&mut self, gui: &mut G, delta_t: f32) {
+    // true if `thread` has commands queued or being drawn
+    fn is_drawing_for(&self, thread: TurtleThread) -> bool {
+        (self.data.current_command.is_some() && self.data.current_thread == thread)
+            || self.data.queue.iter().any(|cmd| cmd.thread == thread)
+    }
+
+    // draw everything that's queued straight away, without animating it
+    fn finish_commands<G: TurtleGui>(&mut self, gui: &mut G) {
+        self.time_passes(gui, 0., false);
+    }
+
+    // with `animate` off, every queued command is finished at once
+    fn time_passes<G: TurtleGui>(&mut self, gui: &mut G, delta_t: f32, animate: bool) {
         let s = self.data.speed.get();
 
-        self.data.drawing_done = s == 0
+        self.data.drawing_done = !animate
+            || s == 0
             || match self.data.progression {
                 Progression::Forward => self.data.percent >= 1.,
                 Progression::Reverse => self.data.percent <= 0.,
@@ -392,19 +808,29 @@ impl TurtleData {
         if self.data.drawing_done {
             self.data.percent = 1.;
         } else {
-            let multiplier = s as f32;
+            let (distance, rotation) = self.data.motion;
+            let duration = self.data.speed.duration(distance, rotation);
+            let step = if duration > 0. {
+                delta_t / duration
+            } else {
+                1.
+            };
 
             match self.data.progression {
-                Progression::Forward => self.data.percent += delta_t * multiplier,
-                Progression::Reverse => self.data.percent -= delta_t * multiplier,
+                Progression::Forward => self.data.percent += step,
+                Progression::Reverse => self.data.percent -= step,
             }
+            self.data.percent = self.data.percent.clamp(0., 1.);
         }
 
-        if !self.data.tracer && !self.data.queue.is_empty() {
-            while !self.data.tracer && !self.data.queue.is_empty() {
-                self.data.drawing_done = true;
-                self.do_next_command(gui);
-            }
+        while !animate && !self.data.queue.is_empty() {
+            self.data.drawing_done = true;
+            self.do_next_command(gui);
+        }
+        if !animate {
+            // the last command isn't left half drawn either
+            self.data.drawing_done = true;
+            self.data.percent = 1.;
         }
         self.do_next_command(gui);
     }
@@ -414,34 +840,13 @@ impl TurtleData {
             self.data.drawing_done = false;
 
             if matches!(self.data.progression, Progression::Reverse) {
-                if let Some(cmd) = gui.pop(self.data.current_turtle) {
-                    match cmd {
-                        DrawCommand::Line(line) => {
-                            let x = line.begin.x as f32;
-                            let y = line.begin.y as f32;
-                            self.data.current_shape.transform = Transform2D::translation(x, y);
-                        }
-                        DrawCommand::SetHeading(start, _) => {
-                            self.data.current_shape.angle = start;
-                        }
-                        _ => {}
-                    }
-                }
+                self.undo(gui);
+                self.data.progression = Progression::Forward;
+                self.data.percent = 1.;
             }
 
             let cmd = self.data.current_command.take().unwrap();
-
-            if cmd.tracer_true() {
-                self.data.respond_immediately = false;
-            }
-
-            if !self.data.respond_immediately {
-                self.send_response(self.data.current_thread, cmd.is_stamp());
-            }
-
-            if cmd.tracer_false() {
-                self.data.respond_immediately = true;
-            }
+            self.send_response(self.data.current_thread, cmd.is_stamp());
         }
 
         if self.data.current_command.is_none() && !self.data.queue.is_empty() {
@@ -453,16 +858,31 @@ impl TurtleData {
             self.data.current_turtle = turtle;
             self.data.current_thread = thread;
 
-            self.convert_command(&cmd, gui);
-
-            if let DrawRequest::InstantaneousDraw(InstantaneousDrawCmd::Tracer(t)) = &cmd {
-                self.data.tracer = *t;
+            if matches!(cmd, DrawRequest::TimedDraw(TimedDrawCmd::Redo)) {
+                // draw the undone command again, just as it was drawn before
+                if let Some(redo) = self.data.undo_buffer.pop_redo() {
+                    self.save_undo(&redo, gui);
+                    self.convert_command(&redo, gui);
+                }
+            } else {
+                if cmd.is_undoable() {
+                    self.data.undo_buffer.clear_redo();
+                }
+                self.save_undo(&cmd, gui);
+                self.convert_command(&cmd, gui);
             }
 
             if matches!(cmd, DrawRequest::TimedDraw(TimedDrawCmd::Undo)) {
-                self.data.progression = Progression::Reverse;
+                // lines, turns and circles are drawn backwards before they go
+                if let Some(motion) = self.undo_motion(gui) {
+                    self.data.progression = Progression::Reverse;
+                    self.data.motion = motion;
+                    gui.undo(turtle);
+                } else {
+                    self.undo(gui);
+                    self.data.progression = Progression::Forward;
+                }
                 self.data.percent = 1.;
-                gui.undo(turtle);
             } else {
                 self.data.progression = Progression::Forward;
                 self.data.percent = 0.;
@@ -473,22 +893,45 @@ impl TurtleData {
     }
 
     fn send_response(&mut self, thread: TurtleThread, is_stamp: bool) {
-        let _ = self.data.responder[&thread].send(if is_stamp {
-            Response::StampID(self.data.current_stamp)
-        } else {
-            Response::Done
-        });
+        // nobody is listening if the turtle was dropped before it finished drawing
+        if let Some(responder) = self.data.responder.get(&thread) {
+            let _ = responder.send(if is_stamp {
+                Response::StampID(self.data.current_stamp)
+            } else {
+                Response::Done
+            });
+        }
     }
 }
 
+struct TurtleTimer {
+    turtle: TurtleID,
+    func: Callback<TimerHandler>,
+    due: Instant,
+    interval: Option<Duration>, // repeating timers only
+    handle: TimerHandle,
+}
+
 #[derive(Default)]
 pub(crate) struct TurtleTask {
     issue_command: Option<Sender<Request>>,
     receive_command: Option<Receiver<Request>>,
     bgcolor: TurtleColor,
+    colormode: f32,
     data: Vec<TurtleData>,
     shapes: HashMap<String, TurtleShape>,
     winsize: [isize; 2],
+    coords: Coordinates,
+    timers: Vec<TurtleTimer>,
+    last_tick: Option<Instant>,
+    onkeypress: Bindings<TurtleKey, KeyBinding>,
+    onkeyrelease: Bindings<TurtleKey, KeyBinding>,
+    onscreenclick: Bindings<MouseButton, ClickBinding>,
+    handling_key: Arc<AtomicBool>, // key events are dropped until the handler returns
+    tracer: usize,                 // updates per frame; 0 shows nothing until `update()`
+    tracing: Arc<AtomicBool>,      // read by every turtle handle, which stops waiting when off
+    delay: Duration,               // between updates, which paces the animation
+    update_reply: Option<Sender<Response>>, // answered once the frame is shown
 }
 
 type TurtleStartFunc = dyn FnOnce(&mut Turtle) + Send + 'static;
@@ -511,6 +954,11 @@ impl TurtleTask {
             receive_command,
             data: vec![TurtleData::new()],
             shapes: generate_default_shapes(),
+            bgcolor: TurtleColor::from("white"),
+            colormode: 1.,
+            tracer: 1,
+            tracing: Arc::new(AtomicBool::new(true)),
+            delay: Duration::from_millis(10),
             ..Self::default()
         }
     }
@@ -519,25 +967,7 @@ impl TurtleTask {
         (self.data[tid].data.percent, self.data[tid].data.progression)
     }
 
-    pub(crate) fn popup_result(
-        &mut self,
-        turtle: TurtleID,
-        thread: TurtleThread,
-        response: Response,
-    ) {
-        let _ = self.data[turtle].data.responder[&thread].send(response);
-    }
-
-    pub(crate) fn popup_cancelled(&mut self, turtle: TurtleID, thread: TurtleThread) {
-        let _ = self.data[turtle].data.responder[&thread].send(Response::Cancel);
-    }
-
-    pub(crate) fn handle_event(
-        &mut self,
-        turtle: Option<TurtleID>,
-        thread: Option<TurtleThread>,
-        event: TurtleEvent,
-    ) {
+    pub(crate) fn handle_event<G: TurtleGui>(&mut self, event: TurtleEvent, gui: &G) {
         use TurtleEvent::*;
 
         macro_rules! spawn {
@@ -545,12 +975,17 @@ impl TurtleTask {
                 let _turtle = TurtleID::new($idx);
                 let _thread = $td.data.next_thread.get();
 
-                let mut _new_turtle = $td.spawn(_turtle, _thread, self.issue_command.as_ref().unwrap().clone());
+                let mut _new_turtle = $td.spawn(
+                    _turtle,
+                    _thread,
+                    self.issue_command.as_ref().unwrap().clone(),
+                    self.tracing.clone(),
+                );
 
                 let _ = std::thread::spawn(move || {
                     // $($args)* will expand to *anything* you pass in to the macro after the func
-                    $func(&mut _new_turtle, $($args)*);
-                    let _ = _new_turtle.issue_command.send(Request::shut_down(_turtle, _thread));
+                    let mut _func = $func.lock();
+                    (*_func)(&mut _new_turtle, $($args)*);
                 });
             }};
         }
@@ -558,80 +993,221 @@ impl TurtleTask {
         match event {
             WindowResize(width, height) => {
                 self.winsize = [width as isize, height as isize];
-                if turtle.is_none() {
-                    assert!(thread.is_none());
-                } else {
-                    let turtle = turtle.expect("missing turtle from window resize");
-                    let thread = thread.expect("missing thread from window resize");
-                    let _ = self.data[turtle].data.responder[&thread].send(Response::Done);
+            }
+            KeyPress(key, modifiers) => {
+                if let Some(binding) = bound(&self.onkeypress, &key) {
+                    self.run_key_handler(binding, key, modifiers);
                 }
             }
-            KeyPress(ch) => {
-                for (idx, turtle) in self.data.iter_mut().enumerate() {
-                    if let Some(func) = turtle.data.onkeypress.get(&ch).copied() {
-                        if !turtle.data.pending_key_event() {
-                            spawn!(turtle, idx, func, ch);
-                        }
-                    }
+            KeyRelease(key, modifiers) => {
+                if let Some(binding) = bound(&self.onkeyrelease, &key) {
+                    self.run_key_handler(binding, key, modifiers);
                 }
             }
-            KeyRelease(ch) => {
-                for (idx, turtle) in self.data.iter_mut().enumerate() {
-                    if let Some(func) = turtle.data.onkeyrelease.get(&ch).copied() {
-                        if !turtle.data.pending_key_event() {
-                            spawn!(turtle, idx, func, ch);
-                        }
+            MousePress(x, y, button) => {
+                match bound(&self.onscreenclick, &button) {
+                    Some(Binding::Turtle(turtle, func)) => {
+                        let idx = turtle.index();
+                        spawn!(self.data[turtle], idx, func, x, y, button);
+                    }
+                    Some(Binding::Screen(func)) => {
+                        let screen = Screen::new(self.issue_command.as_ref().unwrap().clone());
+                        let _ = std::thread::spawn(move || (*func.lock())(&screen, x, y, button));
                     }
+                    None => {}
                 }
-            }
-            MousePress(x, y) => {
+
+                let hits: Vec<bool> = (0..self.data.len())
+                    .map(|idx| self.hit_test(TurtleID::new(idx), x, y, gui))
+                    .collect();
+
                 for (idx, turtle) in self.data.iter_mut().enumerate() {
-                    if let Some(func) = turtle.data.onmousepress {
-                        spawn!(turtle, idx, func, x, y);
+                    // drags and releases go to the turtles that were clicked on
+                    turtle.data.grabbed = hits[idx];
+                    if hits[idx] {
+                        if let Some(func) = bound(&turtle.data.onmousepress, &button) {
+                            spawn!(turtle, idx, func, x, y, button);
+                        }
                     }
                 }
             }
-            MouseRelease(x, y) => {
+            MouseRelease(x, y, button) => {
                 for (idx, turtle) in self.data.iter_mut().enumerate() {
-                    if let Some(func) = turtle.data.onmouserelease {
-                        spawn!(turtle, idx, func, x, y);
+                    if std::mem::take(&mut turtle.data.grabbed) {
+                        if let Some(func) = bound(&turtle.data.onmouserelease, &button) {
+                            spawn!(turtle, idx, func, x, y, button);
+                        }
                     }
                 }
             }
             MousePosition(_, _) => todo!(),
-            MouseDrag(x, y) => {
+            MouseDrag(x, y, button) => {
                 for (idx, turtle) in self.data.iter_mut().enumerate() {
-                    if let Some(func) = turtle.data.onmousedrag {
-                        spawn!(turtle, idx, func, x, y);
+                    if turtle.data.grabbed {
+                        if let Some(func) = bound(&turtle.data.onmousedrag, &button) {
+                            spawn!(turtle, idx, func, x, y, button);
+                        }
                     }
                 }
             }
-            _Timer => todo!(),
             Unhandled => {}
         }
     }
 
-    pub(crate) fn run_turtle<F: FnOnce(&mut Turtle) + Send + 'static>(&mut self, func: F) {
-        let mut turtle = self.spawn_turtle(TurtleID::new(0), TurtleThread::new(0));
-        let _ = std::thread::spawn(move || func(&mut turtle));
+    // Run a key handler on its own thread. Keys that are pressed while it's
+    // still running are ignored, so that held keys don't pile up.
+    fn run_key_handler(&mut self, binding: KeyBinding, key: TurtleKey, modifiers: KeyModifiers) {
+        if self.handling_key.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let handling_key = self.handling_key.clone();
+        let issue_command = self.issue_command.as_ref().unwrap().clone();
+
+        match binding {
+            Binding::Turtle(turtle, func) => {
+                let thread = self.data[turtle].data.next_thread.get();
+                let tracing = self.tracing.clone();
+                let mut new_turtle =
+                    self.data[turtle].spawn(turtle, thread, issue_command, tracing);
+                let _ = std::thread::spawn(move || {
+                    (*func.lock())(&mut new_turtle, key, modifiers);
+                    handling_key.store(false, Ordering::Release);
+                });
+            }
+            Binding::Screen(func) => {
+                let screen = Screen::new(issue_command);
+                let _ = std::thread::spawn(move || {
+                    (*func.lock())(&screen, key, modifiers);
+                    handling_key.store(false, Ordering::Release);
+                });
+            }
+        }
+    }
+
+    // is the (turtle coordinate) point x, y on top of the turtle?
+    fn hit_test<G: TurtleGui>(&self, turtle: TurtleID, x: f32, y: f32, gui: &G) -> bool {
+        let Some(shape) = gui.visible_shape(turtle) else {
+            return false;
+        };
+
+        // the shape isn't stretched along with the rest of the canvas, so
+        // compare them on the window rather than on the canvas
+        let state = &self.data[turtle].data.current_shape;
+        let [vx, vy] = self
+            .coords
+            .view([self.winsize[0] as f32, self.winsize[1] as f32]);
+        let pos: ScreenPosition<f32> = state.pos();
+        let point = self.coords.canvas_pos(x, y);
+
+        let transform =
+            Transform2D::rotation(Angle::degrees(scale_angle(state.canvas_angle(), [vx, vy])))
+                .then_translate([pos.x * vx, pos.y * vy].into());
+        transform
+            .inverse()
+            .map(|inverse| {
+                let point =
+                    inverse.transform_point(ScreenPosition::new(point.x * vx, point.y * vy));
+                shape.shape.contains(point)
+            })
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn run_turtle<F: FnOnce(&mut Turtle) + Send + 'static>(
+        &mut self,
+        func: F,
+    ) -> JoinHandle<()> {
+        // take the thread id from the counter, so event handlers don't reuse it
+        let thread = self.data[0].data.next_thread.get();
+        let mut turtle = self.spawn_turtle(TurtleID::new(0), thread);
+        std::thread::spawn(move || func(&mut turtle))
+    }
+
+    // true when no turtle has anything left to draw
+    pub(crate) fn is_idle(&self) -> bool {
+        self.data
+            .iter()
+            .all(|td| td.data.queue.is_empty() && td.data.current_command.is_none())
     }
 
     pub(crate) fn tick<G: TurtleGui>(&mut self, gui: &mut G) {
-        while let Ok(req) = self.receive_command.as_ref().unwrap().try_recv() {
+        let now = Instant::now();
+        let delta_t = self
+            .last_tick
+            .map(|last| (now - last).as_secs_f32())
+            .unwrap_or_default();
+        self.last_tick = Some(now);
+
+        self.tick_by(gui, delta_t);
+    }
+
+    // advance all the animations by `delta_t` seconds
+    pub(crate) fn tick_by<G: TurtleGui>(&mut self, gui: &mut G, delta_t: f32) {
+        // `update()` ends the frame: anything after it belongs to the next one
+        while self.update_reply.is_none() {
+            let Ok(req) = self.receive_command.as_ref().unwrap().try_recv() else {
+                break;
+            };
             self.handle_command(req, gui);
         }
 
-        for turtle in self.data.iter_mut() {
-            turtle.time_passes(gui, 0.01); // TODO: use actual time delta
+        self.run_timers();
+
+        // speeds are set for python's 10ms between updates, and each frame
+        // takes in `tracer` updates
+        let animate = self.tracer > 0;
+        let delta_t = delta_t * 0.01 / self.delay.as_secs_f32().max(0.001);
+        for _ in 0..self.tracer.max(1) {
+            for turtle in self.data.iter_mut() {
+                turtle.time_passes(gui, delta_t, animate);
+            }
+            if self.is_idle() {
+                break;
+            }
+        }
+    }
+
+    // true if the gui should show what has been drawn since the last frame;
+    // with tracing off, that's only when `update()` asks for it
+    pub(crate) fn take_update(&self) -> bool {
+        self.update_reply.is_some() || self.tracer > 0
+    }
+
+    // the gui has shown the frame, so a waiting `update()` can return
+    pub(crate) fn frame_shown(&mut self) {
+        if let Some(reply) = self.update_reply.take() {
+            let _ = reply.send(Response::Done);
+        }
+    }
+
+    fn run_timers(&mut self) {
+        let now = Instant::now();
+        self.timers.retain(|timer| !timer.handle.is_cancelled());
+
+        let mut expired = Vec::new();
+        for timer in self.timers.iter_mut() {
+            if timer.due <= now {
+                expired.push((timer.turtle, timer.func.clone()));
+                if let Some(interval) = timer.interval {
+                    timer.due += interval;
+                } else {
+                    timer.handle.cancel();
+                }
+            }
+        }
+
+        for (turtle, func) in expired {
+            let thread = self.data[turtle].data.next_thread.get();
+            let mut new_turtle = self.spawn_turtle(turtle, thread);
+            let _ = std::thread::spawn(move || (*func.lock())(&mut new_turtle));
         }
     }
 
     pub(crate) fn hatch_turtle<G: TurtleGui>(&mut self, gui: &mut G) -> Turtle {
         let (finished, command_complete) = mpsc::channel();
         let turtle = gui.new_turtle();
-        let thread = TurtleThread::new(0);
-
         let mut td = TurtleData::new();
+        td.data.current_shape.coords = self.coords;
+        let thread = td.data.next_thread.get();
         td.data.responder.insert(thread, finished);
         self.data.push(td);
 
@@ -640,66 +1216,175 @@ impl TurtleTask {
             command_complete,
             turtle,
             thread,
+            self.tracing.clone(),
         )
     }
 
     fn spawn_turtle(&mut self, turtle: TurtleID, thread: TurtleThread) -> Turtle {
-        self.data[turtle].spawn(turtle, thread, self.issue_command.as_ref().unwrap().clone())
+        let issue_command = self.issue_command.as_ref().unwrap().clone();
+        self.data[turtle].spawn(turtle, thread, issue_command, self.tracing.clone())
     }
 
-    fn screen_cmd<G: TurtleGui>(
+    // every turtle, and the gui, share the same coordinates
+    fn set_coordinates<G: TurtleGui>(&mut self, gui: &mut G) {
+        for turtle in self.data.iter_mut() {
+            turtle.data.current_shape.coords = self.coords;
+        }
+        gui.set_coordinates(self.coords);
+    }
+
+    fn turtle_cmd<G: TurtleGui>(
         &mut self,
         turtle: TurtleID,
-        cmd: ScreenCmd,
+        cmd: TurtleCmd,
         thread: TurtleThread,
         gui: &mut G,
     ) {
-        let resp = self.data[turtle]
-            .data
-            .responder
-            .get(&thread)
-            .unwrap()
-            .clone();
         match cmd {
-            ScreenCmd::SetSize(s) => {
-                gui.resize(turtle, thread, s[0], s[1]);
-                // Note: don't send "done" here -- wait for the resize event from the GUI
-            }
-            ScreenCmd::ShowTurtle(t) => {
+            TurtleCmd::ShowTurtle(t) => {
                 gui.set_visible(turtle, t);
-                let _ = resp.send(Response::Done);
             }
-            ScreenCmd::Speed(s) => {
+            TurtleCmd::Speed(s) => {
                 self.data[turtle].data.speed = s;
-                let _ = resp.send(Response::Done);
             }
-            ScreenCmd::Background(TurtleColor::CurrentColor) => {
-                let _ = resp.send(Response::Done);
+            TurtleCmd::ClearStamp(id) => {
+                if let Some(index) = self.data[turtle].data.stamps.remove(&id) {
+                    gui.clear_stamp(turtle, index);
+                }
             }
-            ScreenCmd::Background(TurtleColor::Color(r, g, b)) => {
-                gui.bgcolor([r, g, b, 1.].into());
-                let _ = resp.send(Response::Done);
+            TurtleCmd::ClearStamps(count) => {
+                self.data[turtle].clear_stamps(turtle, count, gui);
             }
-            ScreenCmd::ClearScreen => {
-                self.bgcolor = TurtleColor::from("black");
-                let _ = resp.send(Response::Done);
+        }
+        let _ = self.data[turtle].data.responder[&thread].send(Response::Done);
+    }
+
+    fn screen_cmd<G: TurtleGui>(&mut self, cmd: ScreenCmd, reply: Sender<Response>, gui: &mut G) {
+        let response = match cmd {
+            ScreenCmd::SetSize(s) => {
+                // Note: don't reply here -- wait for the resize event from the GUI
+                gui.resize(reply, s[0], s[1]);
+                return;
             }
-            ScreenCmd::ClearStamp(id) => {
-                gui.clear_stamp(turtle, id);
-                let _ = resp.send(Response::Done);
+            ScreenCmd::TextInput(title, prompt) => {
+                gui.textinput(reply, &title, &prompt);
+                return;
             }
-            ScreenCmd::ClearStamps(count) => {
-                #[allow(clippy::comparison_chain)]
-                if count < 0 {
-                    gui.clear_stamps(turtle, StampCount::Reverse((-count) as usize));
-                } else if count == 0 {
-                    gui.clear_stamps(turtle, StampCount::All);
-                } else {
-                    gui.clear_stamps(turtle, StampCount::Forward(count as usize));
+            ScreenCmd::NumInput(title, prompt) => {
+                gui.numinput(reply, &title, &prompt);
+                return;
+            }
+            ScreenCmd::Mode(mode) => {
+                self.coords.set_mode(mode);
+                self.set_coordinates(gui);
+                Response::Done
+            }
+            ScreenCmd::WorldCoordinates(lower_left, upper_right) => {
+                self.coords.set_world(lower_left, upper_right, self.winsize);
+                self.set_coordinates(gui);
+                Response::Done
+            }
+            ScreenCmd::Background(color) => {
+                let color = color.resolve(self.colormode);
+                if matches!(color, TurtleColor::Color(..)) {
+                    self.bgcolor = color;
+                    gui.bgcolor(color);
                 }
-                let _ = resp.send(Response::Done);
+                Response::Done
             }
-        }
+            ScreenCmd::ColorMode(colormode) => {
+                self.colormode = colormode;
+                Response::Done
+            }
+            ScreenCmd::RegisterShape(name, shape) => {
+                let shape = TurtleShape {
+                    name: name.clone(),
+                    shape: shape.with_colormode(self.colormode),
+                };
+                self.shapes.insert(name, shape);
+                Response::Done
+            }
+            ScreenCmd::BgPic(bgpic) => {
+                gui.bgpic(bgpic);
+                Response::Done
+            }
+            ScreenCmd::Tracer(n, delay) => {
+                self.tracer = n;
+                self.tracing.store(n > 0, Ordering::Release);
+                if let Some(delay) = delay {
+                    self.delay = Duration::from_millis(delay);
+                }
+                Response::Done
+            }
+            ScreenCmd::Delay(delay) => {
+                self.delay = Duration::from_millis(delay);
+                Response::Done
+            }
+            ScreenCmd::Update => {
+                // Note: don't reply here -- wait until the gui has shown the frame
+                self.update_reply = Some(reply);
+                return;
+            }
+            ScreenCmd::ClearScreen => {
+                // as in python, the screen goes back to its defaults, with no
+                // event bindings, and every turtle starts again from scratch
+                self.coords = Coordinates::default();
+                self.set_coordinates(gui);
+                self.colormode = 1.;
+                self.tracer = 1;
+                self.tracing.store(true, Ordering::Release);
+                self.delay = Duration::from_millis(10);
+                for (idx, td) in self.data.iter_mut().enumerate() {
+                    td.reset(TurtleID::new(idx), gui);
+                    td.data.onmousepress.clear();
+                    td.data.onmouserelease.clear();
+                    td.data.onmousedrag.clear();
+                }
+                self.onkeypress.clear();
+                self.onkeyrelease.clear();
+                self.onscreenclick.clear();
+                self.bgcolor = TurtleColor::from("white");
+                gui.bgcolor(self.bgcolor);
+                gui.bgpic(None);
+                Response::Done
+            }
+            ScreenCmd::ResetScreen => {
+                for (idx, td) in self.data.iter_mut().enumerate() {
+                    td.reset(TurtleID::new(idx), gui);
+                }
+                Response::Done
+            }
+            ScreenCmd::KeyPress(f, k, h) => {
+                rebind(&mut self.onkeypress, k, f.map(Binding::Screen), h);
+                Response::Done
+            }
+            ScreenCmd::KeyRelease(f, k, h) => {
+                rebind(&mut self.onkeyrelease, k, f.map(Binding::Screen), h);
+                Response::Done
+            }
+            ScreenCmd::ScreenClick(f, b, h) => {
+                rebind(&mut self.onscreenclick, b, f.map(Binding::Screen), h);
+                Response::Done
+            }
+            ScreenCmd::GetScreenSize => Response::ScreenSize(self.winsize),
+            ScreenCmd::GetMode => Response::Mode(self.coords.mode()),
+            ScreenCmd::GetColorMode => Response::ColorMode(self.colormode),
+            ScreenCmd::GetBgColor => Response::Color(self.bgcolor),
+            ScreenCmd::GetTracer => Response::Count(self.tracer),
+            ScreenCmd::GetDelay => Response::Count(self.delay.as_millis() as usize),
+            ScreenCmd::Scene => Response::Scene(gui.scene(self.winsize)),
+            ScreenCmd::Turtles => {
+                let turtles = (0..self.data.len())
+                    .map(|idx| {
+                        let turtle = TurtleID::new(idx);
+                        let thread = self.data[turtle].data.next_thread.get();
+                        self.spawn_turtle(turtle, thread)
+                    })
+                    .collect();
+                Response::Turtles(turtles)
+            }
+        };
+        let _ = reply.send(response);
     }
 
     fn input_cmd(&mut self, turtle: TurtleID, cmd: InputCmd, thread: TurtleThread) {
@@ -710,24 +1395,41 @@ impl TurtleTask {
             .unwrap()
             .clone();
         match cmd {
-            InputCmd::KeyRelease(f, k) => {
-                self.data[turtle].data.onkeyrelease.insert(k, f);
+            InputCmd::KeyRelease(f, k, h) => {
+                let f = f.map(|f| Binding::Turtle(turtle, f));
+                rebind(&mut self.onkeyrelease, k, f, h);
+                let _ = resp.send(Response::Done);
+            }
+            InputCmd::KeyPress(f, k, h) => {
+                let f = f.map(|f| Binding::Turtle(turtle, f));
+                rebind(&mut self.onkeypress, k, f, h);
                 let _ = resp.send(Response::Done);
             }
-            InputCmd::KeyPress(f, k) => {
-                self.data[turtle].data.onkeypress.insert(k, f);
+            InputCmd::MouseDrag(f, b, h) => {
+                rebind(&mut self.data[turtle].data.onmousedrag, b, f, h);
                 let _ = resp.send(Response::Done);
             }
-            InputCmd::MouseDrag(f) => {
-                self.data[turtle].data.onmousedrag = Some(f);
+            InputCmd::MousePress(f, b, h) => {
+                rebind(&mut self.data[turtle].data.onmousepress, b, f, h);
                 let _ = resp.send(Response::Done);
             }
-            InputCmd::MousePress(f) => {
-                self.data[turtle].data.onmousepress = Some(f);
+            InputCmd::MouseRelease(f, b, h) => {
+                rebind(&mut self.data[turtle].data.onmouserelease, b, f, h);
                 let _ = resp.send(Response::Done);
             }
-            InputCmd::MouseRelease(f) => {
-                self.data[turtle].data.onmouserelease = Some(f);
+            InputCmd::ScreenClick(f, b, h) => {
+                let f = f.map(|f| Binding::Turtle(turtle, f));
+                rebind(&mut self.onscreenclick, b, f, h);
+                let _ = resp.send(Response::Done);
+            }
+            InputCmd::Timer(func, delay, repeat, handle) => {
+                self.timers.push(TurtleTimer {
+                    turtle,
+                    func,
+                    due: Instant::now() + delay,
+                    interval: repeat.then_some(delay),
+                    handle,
+                });
                 let _ = resp.send(Response::Done);
             }
         }
@@ -740,6 +1442,12 @@ impl TurtleTask {
         thread: TurtleThread,
         gui: &mut G,
     ) {
+        // commands that weren't waited for are drawn before the turtle is
+        // looked at, so the answer includes them
+        if self.data[turtle].is_drawing_for(thread) {
+            self.data[turtle].finish_commands(gui);
+        }
+
         let resp = self.data[turtle]
             .data
             .responder
@@ -748,31 +1456,57 @@ impl TurtleTask {
             .clone();
 
         let _ = match &cmd {
-            DataCmd::GetScreenSize => resp.send(Response::ScreenSize(self.winsize)),
             DataCmd::Visibility => resp.send(Response::Visibility(gui.is_visible(turtle))),
             DataCmd::GetPoly => resp.send(Response::Polygon(
-                self.data[turtle].data.shape_poly.verticies.clone(),
+                self.data[turtle]
+                    .data
+                    .shape_poly
+                    .verticies
+                    .iter()
+                    .map(|&[x, y]| self.coords.user_pos(ScreenPosition::new(x, y)))
+                    .collect(),
             )),
             DataCmd::TurtleShape(shape) => {
+                // unknown names leave the shape as it is
                 if let TurtleShapeName::Shape(name) = shape {
-                    gui.set_shape(turtle, self.shapes[name].clone());
+                    if let Some(shape) = self.shapes.get(name) {
+                        self.data[turtle].data.shape = shape.clone();
+                        self.data[turtle].update_shape(turtle, gui);
+                    }
                 }
                 resp.send(Response::Name(gui.get_turtle_shape_name(turtle)))
             }
-            DataCmd::UndoBufferEntries => resp.send(Response::Count(gui.undo_count(turtle))),
-            DataCmd::Towards(xpos, ypos) => {
-                let curpos: ScreenPosition<f32> = self.data[turtle].data.current_shape.pos();
-                let x = xpos - curpos.x;
-                let y = ypos + curpos.y;
-                let heading = y.atan2(x) * 360. / (2.0 * PI);
-
-                resp.send(Response::Heading(heading))
+            DataCmd::UndoBufferEntries => {
+                resp.send(Response::Count(self.data[turtle].data.undo_buffer.len()))
             }
-            DataCmd::Position => resp.send(Response::Position(
-                self.data[turtle].data.current_shape.pos(),
+            DataCmd::RedoBufferEntries => resp.send(Response::Count(
+                self.data[turtle].data.undo_buffer.redo_len(),
+            )),
+            DataCmd::Towards(xpos, ypos) => resp.send(Response::Heading(
+                self.data[turtle].data.current_shape.towards(*xpos, *ypos),
             )),
+            DataCmd::Position => {
+                let [x, y] = self.data[turtle].data.current_shape.user_pos();
+                resp.send(Response::Position(ScreenPosition::new(x, y)))
+            }
             DataCmd::Heading => resp.send(Response::Heading(
-                self.data[turtle].data.current_shape.angle(),
+                self.data[turtle].data.current_shape.heading(),
+            )),
+            DataCmd::PenColor => resp.send(Response::Color(
+                self.data[turtle].data.current_shape.pen_color(),
+            )),
+            DataCmd::FillColor => resp.send(Response::Color(
+                self.data[turtle].data.current_shape.fill_color(),
+            )),
+            DataCmd::Pen => resp.send(Response::Pen(self.data[turtle].pen(turtle, gui))),
+            DataCmd::Filling => resp.send(Response::Filling(
+                self.data[turtle].data.fill_poly.last_point.is_some(),
+            )),
+            DataCmd::ShapeTransform => resp.send(Response::ShapeTransform(
+                self.data[turtle].data.current_shape.shape_transform(),
+            )),
+            DataCmd::TiltAngle => resp.send(Response::Heading(
+                self.data[turtle].data.current_shape.tiltangle(),
             )),
             DataCmd::Stamp => {
                 self.data[turtle].data.queue.push_back(TurtleCommand {
@@ -782,43 +1516,43 @@ impl TurtleTask {
                 });
                 Ok(())
             }
-            DataCmd::NumInput(title, prompt) => {
-                gui.numinput(turtle, thread, title, prompt);
-                Ok(())
-            }
-            DataCmd::TextInput(title, prompt) => {
-                gui.textinput(turtle, thread, title, prompt);
-                Ok(())
-            }
         };
     }
 
     fn draw_cmd(&mut self, turtle: TurtleID, cmd: DrawRequest, thread: TurtleThread) {
-        let is_stamp = cmd.is_stamp();
         self.data[turtle].data.queue.push_back(TurtleCommand {
-            cmd,
+            cmd: cmd.with_colormode(self.colormode),
             turtle,
             thread,
         });
-
-        // FIXME: data commands (Command::Data(_)) require all queued entries to be
-        // processed before sending a response, even if `respond_immediately` is set
-        if self.data[turtle].data.respond_immediately {
-            self.data[turtle].send_response(thread, is_stamp);
-        }
     }
 
     fn handle_command<G: TurtleGui>(&mut self, req: Request, gui: &mut G) {
-        let turtle = req.turtle;
-        let thread = req.thread;
+        let (turtle, thread, cmd) = match req {
+            Request::Turtle {
+                turtle,
+                thread,
+                cmd,
+            } => (turtle, thread, cmd),
+            Request::Screen(cmd, reply) => {
+                // with tracing off, nobody waited for the turtles' commands,
+                // so they come first
+                if self.tracer == 0 {
+                    for td in self.data.iter_mut() {
+                        td.finish_commands(gui);
+                    }
+                }
+                self.screen_cmd(cmd, reply, gui);
+                return;
+            }
+        };
 
-        match req.cmd {
+        match cmd {
             Command::ShutDown => {
                 let tid = self.data[turtle].data.responder.remove(&thread);
-                self.data[turtle].data.pending_keys = false;
                 assert!(tid.is_some());
             }
-            Command::Screen(cmd) => self.screen_cmd(turtle, cmd, thread, gui),
+            Command::Turtle(cmd) => self.turtle_cmd(turtle, cmd, thread, gui),
             Command::Draw(cmd) => self.draw_cmd(turtle, cmd, thread),
             Command::Input(cmd) => self.input_cmd(turtle, cmd, thread),
             Command::Data(cmd) => self.data_cmd(turtle, cmd, thread, gui),
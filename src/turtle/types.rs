@@ -15,6 +15,10 @@ impl TurtleID {
     pub(crate) fn new(id: usize) -> Self {
         Self { id }
     }
+
+    pub(crate) fn index(&self) -> usize {
+        self.id
+    }
 }
 
 #[derive(Debug, Hash, Eq, PartialEq, Default, Copy, Clone)]
@@ -23,10 +27,6 @@ pub(crate) struct TurtleThread {
 }
 
 impl TurtleThread {
-    pub(crate) fn new(thread: usize) -> Self {
-        Self { thread }
-    }
-
     pub(crate) fn get(&mut self) -> Self {
         self.thread += 1;
         Self {
@@ -0,0 +1,268 @@
+use std::collections::VecDeque;
+
+use super::PolygonMark;
+use crate::{
+    command::DrawRequest, generate::CurrentTurtleState, polygon::FillRule, speed::TurtleSpeed,
+};
+
+// python keeps this many by default
+const UNDO_LIMIT: usize = 1000;
+
+// Everything needed to put a turtle back the way it was before one of its
+// commands was drawn
+pub(crate) struct UndoEntry {
+    pub(crate) cmd: DrawRequest, // to draw again if it's redone
+    pub(crate) state: CurrentTurtleState,
+    pub(crate) fill_poly: PolygonMark,
+    pub(crate) shape_poly: PolygonMark,
+    pub(crate) fill_rule: FillRule,
+    pub(crate) insert_fill: Option<usize>,
+    pub(crate) cmds: usize, // how many draw commands the gui had
+    pub(crate) pen: Option<(TurtleSpeed, bool)>, // speed and visibility, for `set_pen()`
+}
+
+// The most recent commands that can be undone, oldest first, and the ones
+// that have been undone, most recently undone last
+pub(crate) struct UndoBuffer {
+    entries: VecDeque<UndoEntry>,
+    limit: Option<usize>, // `None` when undo is turned off
+    redo: Vec<DrawRequest>,
+}
+
+impl Default for UndoBuffer {
+    fn default() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            limit: Some(UNDO_LIMIT),
+            redo: Vec::new(),
+        }
+    }
+}
+
+impl UndoBuffer {
+    // forget everything, and keep at most `limit` entries from now on
+    pub(crate) fn resize(&mut self, limit: Option<usize>) {
+        self.clear();
+        self.limit = limit;
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.redo.clear();
+    }
+
+    pub(crate) fn push(&mut self, entry: UndoEntry) {
+        let Some(limit) = self.limit else {
+            return;
+        };
+        if self.entries.len() >= limit {
+            self.entries.pop_front();
+        }
+        if limit > 0 {
+            self.entries.push_back(entry);
+        }
+    }
+
+    // the entry to undo, which can then be redone
+    pub(crate) fn pop(&mut self) -> Option<UndoEntry> {
+        let entry = self.entries.pop_back()?;
+        self.redo.push(entry.cmd.clone());
+        Some(entry)
+    }
+
+    pub(crate) fn pop_redo(&mut self) -> Option<DrawRequest> {
+        self.redo.pop()
+    }
+
+    // something new was drawn, so what was undone can't come back
+    pub(crate) fn clear_redo(&mut self) {
+        self.redo.clear();
+    }
+
+    pub(crate) fn last(&self) -> Option<&UndoEntry> {
+        self.entries.back()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub(crate) fn redo_len(&self) -> usize {
+        self.redo.len()
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.limit.is_some_and(|limit| limit > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        color_names::TurtleColor,
+        testing::{assert_at, line_ends, run},
+    };
+
+    #[test]
+    fn undo_takes_back_lines() {
+        let scene = run(|turtle| {
+            turtle.forward(10);
+            turtle.forward(20);
+            turtle.undo();
+            assert_at(turtle, 10., 0.);
+            assert_eq!(turtle.undobufferentries(), 1);
+        });
+
+        assert_eq!(line_ends(&scene.turtles[0].commands), [[10., 0.]]);
+    }
+
+    #[test]
+    fn undo_restores_the_pen() {
+        run(|turtle| {
+            turtle.pencolor("red");
+            turtle.penwidth(5);
+            turtle.penup();
+            turtle.begin_fill();
+
+            turtle.undo();
+            assert!(!turtle.filling());
+            turtle.undo();
+            assert!(turtle.isdown());
+            turtle.undo();
+            assert_eq!(turtle.pensize(), 1.);
+            turtle.undo();
+            assert_eq!(turtle.getpencolor(), TurtleColor::from("black"));
+        });
+    }
+
+    #[test]
+    fn undo_with_nothing_left() {
+        let scene = run(|turtle| {
+            turtle.forward(10);
+            turtle.undo();
+            turtle.undo();
+            assert_at(turtle, 0., 0.);
+            assert_eq!(turtle.undobufferentries(), 0);
+        });
+
+        assert!(line_ends(&scene.turtles[0].commands).is_empty());
+    }
+
+    #[test]
+    fn default_buffer_size() {
+        run(|turtle| {
+            for _ in 0..1005 {
+                turtle.left(1);
+            }
+            assert_eq!(turtle.undobufferentries(), 1000);
+        });
+    }
+
+    #[test]
+    fn limited_buffer() {
+        let scene = run(|turtle| {
+            turtle.setundobuffer(3);
+            for _ in 0..5 {
+                turtle.forward(10);
+            }
+            assert_eq!(turtle.undobufferentries(), 3);
+
+            for _ in 0..5 {
+                turtle.undo();
+            }
+            assert_at(turtle, 20., 0.);
+        });
+
+        assert_eq!(
+            line_ends(&scene.turtles[0].commands),
+            [[10., 0.], [20., 0.]]
+        );
+    }
+
+    #[test]
+    fn setundobuffer_forgets_the_history() {
+        run(|turtle| {
+            turtle.forward(10);
+            turtle.setundobuffer(10);
+            assert_eq!(turtle.undobufferentries(), 0);
+            turtle.undo();
+            assert_at(turtle, 10., 0.);
+        });
+    }
+
+    #[test]
+    fn undo_turned_off() {
+        let scene = run(|turtle| {
+            turtle.setundobuffer(None);
+            turtle.forward(10);
+            assert_eq!(turtle.undobufferentries(), 0);
+            turtle.undo();
+            assert_at(turtle, 10., 0.);
+        });
+
+        assert_eq!(line_ends(&scene.turtles[0].commands), [[10., 0.]]);
+    }
+
+    #[test]
+    fn redo_draws_again() {
+        let scene = run(|turtle| {
+            turtle.forward(10);
+            turtle.left(90);
+            turtle.forward(20);
+            turtle.undo();
+            turtle.undo();
+            assert_eq!(turtle.redobufferentries(), 2);
+
+            turtle.redo();
+            assert_eq!(turtle.redobufferentries(), 1);
+            assert_eq!(turtle.heading(), 90.);
+            turtle.redo();
+            assert_eq!(turtle.redobufferentries(), 0);
+            assert_at(turtle, 10., 20.);
+            assert_eq!(turtle.undobufferentries(), 3);
+        });
+
+        let ends = line_ends(&scene.turtles[0].commands);
+        assert_eq!(ends.len(), 2);
+        assert!((ends[1][0] - 10.).abs() < 1e-3 && (ends[1][1] + 20.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn redo_with_nothing_undone() {
+        run(|turtle| {
+            turtle.forward(10);
+            turtle.redo();
+            assert_at(turtle, 10., 0.);
+            assert_eq!(turtle.undobufferentries(), 1);
+        });
+    }
+
+    #[test]
+    fn drawing_forgets_what_was_undone() {
+        run(|turtle| {
+            turtle.forward(10);
+            turtle.forward(10);
+            turtle.undo();
+            turtle.undo();
+            turtle.right(90);
+            assert_eq!(turtle.redobufferentries(), 0);
+
+            turtle.redo();
+            assert_at(turtle, 0., 0.);
+        });
+    }
+
+    #[test]
+    fn redo_restores_the_pen() {
+        run(|turtle| {
+            turtle.pencolor("red");
+            turtle.penup();
+            turtle.undo();
+            turtle.undo();
+            turtle.redo();
+            turtle.redo();
+            assert_eq!(turtle.getpencolor(), TurtleColor::from("red"));
+            assert!(!turtle.isdown());
+        });
+    }
+}